@@ -0,0 +1,117 @@
+#[macro_use]
+extern crate rust_data_structures;
+
+use rust_data_structures::algebra::*;
+use rust_data_structures::segment_tree::lazy_segment_tree::LazySegmentTree;
+use std::time::Instant;
+
+monoid!(Sm, u64, 0, |a, b| a.wrapping_add(b));
+
+effector!(Aq, u64, 0, |a, b| a.wrapping_add(b), Sm, |e, t, sz| Sm(t.0.wrapping_add(e.wrapping_mul(sz as u64))));
+
+// the recursive implementation the iterative tree replaced
+struct RecursiveLazySegmentTree<T: Monoid, E: Effector<Target=T>> {
+    node: Vec<T>,
+    lazy: Vec<E>,
+    sz: usize,
+}
+
+impl<T: Monoid, E: Effector<Target=T>> RecursiveLazySegmentTree<T, E> {
+    fn init(vec: Vec<T>) -> Self {
+        let mut sz = 1;
+        while sz < vec.len() { sz *= 2 }
+        let mut node = vec![T::identity(); sz << 1];
+        let lazy = vec![E::identity(); sz << 1];
+        node[sz..(vec.len() + sz)].clone_from_slice(&vec);
+        for i in (1..sz).rev() { node[i] = node[i << 1].op(&node[(i << 1) + 1]); }
+        Self { node, lazy, sz }
+    }
+
+    fn push(&mut self, i: usize, sz: usize) {
+        self.node[i] = self.lazy[i].effect(&self.node[i], sz);
+        if (i << 1) < self.node.len() {
+            self.lazy[i << 1] = self.lazy[i << 1].op(&self.lazy[i]);
+            self.lazy[(i << 1) + 1] = self.lazy[(i << 1) + 1].op(&self.lazy[i]);
+        }
+        self.lazy[i] = E::identity();
+    }
+
+    fn update_raw(&mut self, i: usize, a: usize, b: usize, l: usize, r: usize, e: &E) {
+        self.push(i, r - l);
+        if b <= l || r <= a { return; }
+        if a <= l && r <= b {
+            self.lazy[i] = self.lazy[i].op(e);
+            self.push(i, r - l);
+        }
+        else {
+            self.update_raw(i << 1, a, b, l, (l + r) >> 1, e);
+            self.update_raw((i << 1) + 1, a, b, (l + r) >> 1, r, e);
+            self.node[i] = self.node[i << 1].op(&self.node[(i << 1) + 1]);
+        }
+    }
+
+    fn update_range(&mut self, l: usize, r: usize, e: E) {
+        let sz = self.sz;
+        self.update_raw(1, l, r, 0, sz, &e);
+    }
+
+    fn fold_raw(&mut self, i: usize, a: usize, b: usize, l: usize, r: usize) -> T {
+        self.push(i, r - l);
+        if b <= l || r <= a { T::identity() }
+        else if a <= l && r <= b { self.node[i].clone() }
+        else {
+            self.fold_raw(i << 1, a, b, l, (l + r) >> 1)
+                .op(&self.fold_raw((i << 1) + 1, a, b, (l + r) >> 1, r))
+        }
+    }
+
+    fn fold(&mut self, l: usize, r: usize) -> T {
+        let sz = self.sz;
+        self.fold_raw(1, l, r, 0, sz)
+    }
+}
+
+// (l, r, Some(x)) adds x on [l, r), (l, r, None) folds [l, r)
+fn queries(n: usize, q: usize) -> Vec<(usize, usize, Option<u64>)> {
+    let mut x: u64 = 88172645463325252;
+    let mut next = || {
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        x as usize
+    };
+    (0..q).map(|_| {
+        let a = next() % n;
+        let b = next() % n;
+        let (l, r) = if a < b { (a, b + 1) } else { (b, a + 1) };
+        if next() % 2 == 0 { (l, r, Some(next() as u64 % 100)) } else { (l, r, None) }
+    }).collect()
+}
+
+fn main() {
+    let n = 1 << 20;
+    let q = 1_000_000;
+    let qs = queries(n, q);
+
+    let mut seg = LazySegmentTree::init((0..n).map(|i| Sm(i as u64)).collect());
+    let mut check = 0u64;
+    let start = Instant::now();
+    for &(l, r, x) in qs.iter() {
+        match x {
+            Some(x) => seg.update_range(l, r, Aq(x)),
+            None => check = check.wrapping_add(seg.fold(l..r).0),
+        }
+    }
+    println!("iterative: n = {}, {} mixed queries: {:?} (checksum {})", n, q, start.elapsed(), check);
+
+    let mut seg = RecursiveLazySegmentTree::init((0..n).map(|i| Sm(i as u64)).collect());
+    let mut check = 0u64;
+    let start = Instant::now();
+    for &(l, r, x) in qs.iter() {
+        match x {
+            Some(x) => seg.update_range(l, r, Aq(x)),
+            None => check = check.wrapping_add(seg.fold(l, r).0),
+        }
+    }
+    println!("recursive: n = {}, {} mixed queries: {:?} (checksum {})", n, q, start.elapsed(), check);
+}
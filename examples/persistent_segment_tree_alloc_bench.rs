@@ -0,0 +1,110 @@
+#[macro_use]
+extern crate rust_data_structures;
+
+use rust_data_structures::segment_tree::persistent_segment_tree::PersistentSegmentTree;
+use rust_data_structures::segment_tree::persistent_lazy_segment_tree::PersistentLazySegmentTree;
+use rust_data_structures::segment_tree::arena_persistent_segment_tree::ArenaPersistentSegmentTree;
+use rust_data_structures::segment_tree::arena_persistent_lazy_segment_tree::ArenaPersistentLazySegmentTree;
+use std::alloc::{ GlobalAlloc, Layout, System };
+use std::sync::atomic::{ AtomicUsize, Ordering };
+use std::time::Instant;
+
+struct CountingAlloc;
+
+static ALLOCS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+monoid!(Sm, u64, 0, |a, b| a.wrapping_add(b));
+
+effector!(Aq, u64, 0, |a, b| a.wrapping_add(b), Sm, |e, t, sz| Sm(t.0.wrapping_add(e.wrapping_mul(sz as u64))));
+
+fn queries(n: usize, q: usize) -> Vec<(usize, usize, u64)> {
+    let mut x: u64 = 88172645463325252;
+    let mut next = || {
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        x
+    };
+    (0..q).map(|_| {
+        let a = next() as usize % n;
+        let b = next() as usize % n;
+        let (l, r) = if a < b { (a, b + 1) } else { (b, a + 1) };
+        (l, r, next() % 100)
+    }).collect()
+}
+
+fn report(name: &str, start: Instant, allocs: usize, check: u64) {
+    println!("{:<24} {:>12} allocations {:>10.2?} (checksum {})", name, ALLOCS.load(Ordering::Relaxed) - allocs, start.elapsed(), check);
+}
+
+fn main() {
+    let n = 1 << 17;
+    let q = 200_000;
+    let qs = queries(n, q);
+    let init: Vec<Sm> = (0..n).map(|i| Sm(i as u64)).collect();
+
+    let (allocs, start) = (ALLOCS.load(Ordering::Relaxed), Instant::now());
+    {
+        let mut versions = vec![PersistentSegmentTree::from_vec(init.clone())];
+        let mut check = 0u64;
+        for &(l, r, x) in qs.iter() {
+            let seg = versions[x as usize % versions.len()].update(l, Sm(x));
+            check = check.wrapping_add(seg.fold(l..r).0);
+            versions.push(seg);
+        }
+        report("rc update", start, allocs, check);
+    }
+
+    let (allocs, start) = (ALLOCS.load(Ordering::Relaxed), Instant::now());
+    {
+        let mut seg = ArenaPersistentSegmentTree::from_vec(init.clone());
+        let mut check = 0u64;
+        for &(l, r, x) in qs.iter() {
+            let v = seg.update(x as usize % (seg.latest() + 1), l, Sm(x));
+            check = check.wrapping_add(seg.fold(v, l..r).0);
+        }
+        report("arena update", start, allocs, check);
+    }
+
+    let (allocs, start) = (ALLOCS.load(Ordering::Relaxed), Instant::now());
+    {
+        let mut seg = PersistentLazySegmentTree::from_vec(init.clone());
+        let mut check = 0u64;
+        for &(l, r, x) in qs.iter() {
+            seg = seg.effect_range(l, r, Aq(x));
+            check = check.wrapping_add(seg.fold(l..r).0);
+        }
+        report("rc effect_range", start, allocs, check);
+    }
+
+    let (allocs, start) = (ALLOCS.load(Ordering::Relaxed), Instant::now());
+    {
+        let mut seg = ArenaPersistentLazySegmentTree::from_vec(init.clone());
+        let mut check = 0u64;
+        for &(l, r, x) in qs.iter() {
+            let old = seg.latest();
+            let v = seg.effect_range(old, l, r, Aq(x));
+            seg.release(old);
+            check = check.wrapping_add(seg.fold(v, l..r).0);
+            if seg.node_count() > 8 * n { seg.compact(); }
+        }
+        report("arena effect_range", start, allocs, check);
+    }
+}
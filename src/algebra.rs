@@ -12,7 +12,61 @@ pub trait Monoid: Magma + Associative + Unital {}
 
 impl<T: Magma + Associative + Unital> Monoid for T {}
 
+pub trait Invertible: Magma {
+  fn inverse(&self) -> Self;
+}
+
+pub trait Group: Monoid + Invertible {}
+
+impl<T: Monoid + Invertible> Group for T {}
+
+pub trait Commutative: Magma {}
+
+pub trait Idempotent: Magma {}
+
 pub trait Effector: Monoid {
     type Target;
     fn effect(&self, t: &Self::Target, sz: usize) -> Self::Target;
 }
+
+#[macro_export]
+macro_rules! monoid {
+    ($(#[$attr:meta])* $vis:vis $name:ident, $t:ty, $id:expr, |$a:ident, $b:ident| $op:expr $(; $($marker:ident),+)?) => {
+        #[derive(Clone)]
+        $(#[$attr])*
+        $vis struct $name(pub $t);
+
+        impl $crate::algebra::Magma for $name {
+            fn op(&self, right: &Self) -> Self {
+                let $a: $t = self.0.clone();
+                let $b: $t = right.0.clone();
+                $name($op)
+            }
+        }
+        impl $crate::algebra::Associative for $name {}
+        impl $crate::algebra::Unital for $name {
+            fn identity() -> Self { $name($id) }
+        }
+        $($(impl $crate::algebra::$marker for $name {})+)?
+    };
+}
+
+#[macro_export]
+macro_rules! effector {
+    ($(#[$attr:meta])* $vis:vis $name:ident, $t:ty, $id:expr, |$a:ident, $b:ident| $op:expr, $target:ty, |$e:ident, $x:ident, $sz:ident| $effect:expr $(; $($marker:ident),+)?) => {
+        $crate::monoid!($(#[$attr])* $vis $name, $t, $id, |$a, $b| $op $(; $($marker),+)?);
+
+        impl $crate::algebra::Effector for $name {
+            type Target = $target;
+            fn effect(&self, t: &$target, sz: usize) -> $target {
+                let $e: $t = self.0.clone();
+                let $x: &$target = t;
+                let $sz: usize = sz;
+                $effect
+            }
+        }
+    };
+}
+
+pub mod monoids;
+pub mod laws;
@@ -0,0 +1,120 @@
+use algebra::*;
+use std::fmt::Debug;
+
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self { Rng(seed) }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        let x = self.0;
+        (x ^ (x >> 33)).wrapping_mul(0xff51afd7ed558ccd) ^ (x >> 29)
+    }
+
+    pub fn gen_range(&mut self, l: u64, r: u64) -> u64 {
+        assert!(l < r, "gen_range(): empty range");
+        l + self.next_u64() % (r - l)
+    }
+
+    pub fn gen_index(&mut self, n: usize) -> usize {
+        self.gen_range(0, n as u64) as usize
+    }
+}
+
+const SEED: u64 = 88172645463325252;
+const MAX_SEGMENT: u64 = 8;
+
+fn segment<T: Monoid, G: FnMut(&mut Rng) -> T>(rng: &mut Rng, gen: &mut G) -> (T, usize) {
+    let sz = rng.gen_range(1, MAX_SEGMENT + 1) as usize;
+    let mut x = gen(rng);
+    for _ in 1..sz { x = x.op(&gen(rng)); }
+    (x, sz)
+}
+
+pub fn check_associative<T, G>(mut gen: G, iter: usize)
+    where T: Magma + Associative + PartialEq + Debug, G: FnMut(&mut Rng) -> T {
+    let mut rng = Rng::new(SEED);
+    for _ in 0..iter {
+        let (a, b, c) = (gen(&mut rng), gen(&mut rng), gen(&mut rng));
+        assert_eq!(a.op(&b).op(&c), a.op(&b.op(&c)), "associativity: a = {:?}, b = {:?}, c = {:?}", a, b, c);
+    }
+}
+
+pub fn check_identity<T, G>(mut gen: G, iter: usize)
+    where T: Monoid + PartialEq + Debug, G: FnMut(&mut Rng) -> T {
+    let mut rng = Rng::new(SEED);
+    for _ in 0..iter {
+        let a = gen(&mut rng);
+        assert_eq!(T::identity().op(&a), a, "left identity: a = {:?}", a);
+        assert_eq!(a.op(&T::identity()), a, "right identity: a = {:?}", a);
+    }
+}
+
+pub fn check_monoid<T, G>(mut gen: G, iter: usize)
+    where T: Monoid + PartialEq + Debug, G: FnMut(&mut Rng) -> T {
+    check_associative(&mut gen, iter);
+    check_identity(&mut gen, iter);
+}
+
+pub fn check_effector<E, GE, GT>(mut gen_e: GE, mut gen_t: GT, iter: usize)
+    where E: Effector + Debug, E::Target: Monoid + PartialEq + Debug, GE: FnMut(&mut Rng) -> E, GT: FnMut(&mut Rng) -> E::Target {
+    check_monoid(&mut gen_t, iter);
+    let mut rng = Rng::new(SEED);
+    for _ in 0..iter {
+        let (e1, e2, e3) = (gen_e(&mut rng), gen_e(&mut rng), gen_e(&mut rng));
+        let (x, sx) = segment(&mut rng, &mut gen_t);
+        let (y, sy) = segment(&mut rng, &mut gen_t);
+        assert_eq!(e1.op(&e2).op(&e3).effect(&x, sx), e1.op(&e2.op(&e3)).effect(&x, sx),
+                   "effector associativity: e1 = {:?}, e2 = {:?}, e3 = {:?}, x = {:?}, sz = {}", e1, e2, e3, x, sx);
+        assert_eq!(E::identity().effect(&x, sx), x,
+                   "effector identity: x = {:?}, sz = {}", x, sx);
+        assert_eq!(e1.op(&E::identity()).effect(&x, sx), e1.effect(&x, sx),
+                   "effector identity: e = {:?}, x = {:?}, sz = {}", e1, x, sx);
+        assert_eq!(E::identity().op(&e1).effect(&x, sx), e1.effect(&x, sx),
+                   "effector identity: e = {:?}, x = {:?}, sz = {}", e1, x, sx);
+        assert_eq!(e1.op(&e2).effect(&x, sx), e2.effect(&e1.effect(&x, sx), sx),
+                   "effector composition: e1 = {:?}, e2 = {:?}, x = {:?}, sz = {}", e1, e2, x, sx);
+        assert_eq!(e1.effect(&x.op(&y), sx + sy), e1.effect(&x, sx).op(&e1.effect(&y, sy)),
+                   "effector distribution: e = {:?}, x = {:?} (sz = {}), y = {:?} (sz = {})", e1, x, sx, y, sy);
+    }
+}
+
+#[cfg(test)]
+mod laws_test {
+    use algebra::laws::*;
+    use algebra::monoids::*;
+
+    fn small(rng: &mut Rng) -> i64 { rng.gen_range(0, 41) as i64 - 20 }
+
+    #[test]
+    fn monoids_test() {
+        check_monoid(|r| Sum(small(r)), 200);
+        check_monoid(|r| Min(small(r)), 200);
+        check_monoid(|r| Max(small(r)), 200);
+        check_monoid(|r| Xor(r.next_u64()), 200);
+        check_monoid(|r| Gcd(r.gen_range(0, 100)), 200);
+        check_monoid(|r| Affine(small(r), small(r)), 200);
+        check_monoid(|r| MinCount(small(r) % 3, r.gen_range(0, 3) as usize), 200);
+        check_monoid(|r| MaxSubarraySum::new(small(r)), 200);
+        check_monoid(|r| (Sum(small(r)), Min(small(r))), 200);
+    }
+
+    #[test]
+    fn effectors_test() {
+        check_effector(|r| AddEffector::<Sum<i64>>(small(r)), |r| Sum(small(r)), 200);
+        check_effector(|r| AddEffector::<Min<i64>>(small(r)), |r| Min(small(r)), 200);
+        check_effector(|r| AssignEffector::<Sum<i64>>(if r.gen_range(0, 2) == 0 { None } else { Some(small(r)) }), |r| Sum(small(r)), 200);
+        check_effector(|r| AssignEffector::<Max<i64>>(if r.gen_range(0, 2) == 0 { None } else { Some(small(r)) }), |r| Max(small(r)), 200);
+        check_effector(|r| AffineEffector::<Sum<i64>>(small(r), small(r)), |r| Sum(small(r)), 200);
+        check_effector(|r| ChminEffector::<Max<i64>>(small(r)), |r| Max(small(r)), 200);
+    }
+
+    effector!(#[derive(Debug)] Ignore, i64, 0, |a, b| a + b, Sum<i64>, |e, t, _sz| Sum(t.0 + e));
+
+    #[test]
+    #[should_panic(expected = "effector distribution")]
+    fn size_ignored_test() {
+        check_effector(|r| Ignore(small(r)), |r| Sum(small(r)), 200);
+    }
+}
@@ -0,0 +1,428 @@
+use algebra::*;
+use std::cmp::{ min, max, Ordering };
+use std::ops::{ Add, Sub, Mul, Rem, BitXor };
+
+pub trait Number: Copy + PartialEq + PartialOrd + Add<Output=Self> + Sub<Output=Self> + Mul<Output=Self> + Rem<Output=Self> {
+    fn zero() -> Self;
+    fn one() -> Self;
+    fn from_usize(n: usize) -> Self;
+    fn wrapping_add(self, right: Self) -> Self;
+    fn wrapping_mul(self, right: Self) -> Self;
+    fn wrapping_neg(self) -> Self;
+}
+
+pub trait Bounded: Copy + Ord {
+    fn min_value() -> Self;
+    fn max_value() -> Self;
+}
+
+macro_rules! impl_number {
+    ($($t:ty),*) => { $(
+        impl Number for $t {
+            fn zero() -> Self { 0 }
+            fn one() -> Self { 1 }
+            fn from_usize(n: usize) -> Self { n as $t }
+            fn wrapping_add(self, right: Self) -> Self { <$t>::wrapping_add(self, right) }
+            fn wrapping_mul(self, right: Self) -> Self { <$t>::wrapping_mul(self, right) }
+            fn wrapping_neg(self) -> Self { <$t>::wrapping_neg(self) }
+        }
+        impl Bounded for $t {
+            fn min_value() -> Self { <$t>::MIN }
+            fn max_value() -> Self { <$t>::MAX }
+        }
+    )* };
+}
+
+impl_number!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+pub trait Scalar {
+    type Value: Copy;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+// wraps around on overflow, so unsigned sums form a group modulo 2^bits
+pub struct Sum<T>(pub T);
+
+impl<T: Number> Magma for Sum<T> {
+    fn op(&self, right: &Self) -> Self { Sum(self.0.wrapping_add(right.0)) }
+}
+impl<T: Number> Associative for Sum<T> {}
+impl<T: Number> Unital for Sum<T> {
+    fn identity() -> Self { Sum(T::zero()) }
+}
+impl<T: Number> Commutative for Sum<T> {}
+impl<T: Number> Invertible for Sum<T> {
+    fn inverse(&self) -> Self { Sum(self.0.wrapping_neg()) }
+}
+impl<T: Copy> Scalar for Sum<T> { type Value = T; }
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Min<T>(pub T);
+
+impl<T: Bounded> Magma for Min<T> {
+    fn op(&self, right: &Self) -> Self { Min(min(self.0, right.0)) }
+}
+impl<T: Bounded> Associative for Min<T> {}
+impl<T: Bounded> Unital for Min<T> {
+    fn identity() -> Self { Min(T::max_value()) }
+}
+impl<T: Bounded> Commutative for Min<T> {}
+impl<T: Bounded> Idempotent for Min<T> {}
+impl<T: Copy> Scalar for Min<T> { type Value = T; }
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Max<T>(pub T);
+
+impl<T: Bounded> Magma for Max<T> {
+    fn op(&self, right: &Self) -> Self { Max(max(self.0, right.0)) }
+}
+impl<T: Bounded> Associative for Max<T> {}
+impl<T: Bounded> Unital for Max<T> {
+    fn identity() -> Self { Max(T::min_value()) }
+}
+impl<T: Bounded> Commutative for Max<T> {}
+impl<T: Bounded> Idempotent for Max<T> {}
+impl<T: Copy> Scalar for Max<T> { type Value = T; }
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Xor<T>(pub T);
+
+impl<T: Number + BitXor<Output=T>> Magma for Xor<T> {
+    fn op(&self, right: &Self) -> Self { Xor(self.0 ^ right.0) }
+}
+impl<T: Number + BitXor<Output=T>> Associative for Xor<T> {}
+impl<T: Number + BitXor<Output=T>> Unital for Xor<T> {
+    fn identity() -> Self { Xor(T::zero()) }
+}
+impl<T: Number + BitXor<Output=T>> Commutative for Xor<T> {}
+impl<T: Number + BitXor<Output=T>> Invertible for Xor<T> {
+    fn inverse(&self) -> Self { *self }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Gcd<T>(pub T);
+
+impl<T: Number> Magma for Gcd<T> {
+    fn op(&self, right: &Self) -> Self {
+        let (mut a, mut b) = (self.0, right.0);
+        while b != T::zero() {
+            let r = a % b;
+            a = b;
+            b = r;
+        }
+        // the sign would otherwise depend on the order of the arguments
+        Gcd(if a < T::zero() { a.wrapping_neg() } else { a })
+    }
+}
+impl<T: Number> Associative for Gcd<T> {}
+impl<T: Number> Unital for Gcd<T> {
+    fn identity() -> Self { Gcd(T::zero()) }
+}
+impl<T: Number> Commutative for Gcd<T> {}
+impl<T: Number> Idempotent for Gcd<T> {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Affine<T>(pub T, pub T);
+
+impl<T: Number> Affine<T> {
+    pub fn apply(&self, x: T) -> T { self.0 * x + self.1 }
+}
+impl<T: Number> Magma for Affine<T> {
+    fn op(&self, right: &Self) -> Self {
+        Affine(right.0 * self.0, right.0 * self.1 + right.1)
+    }
+}
+impl<T: Number> Associative for Affine<T> {}
+impl<T: Number> Unital for Affine<T> {
+    fn identity() -> Self { Affine(T::one(), T::zero()) }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MinCount<T>(pub T, pub usize);
+
+impl<T: Bounded> Magma for MinCount<T> {
+    fn op(&self, right: &Self) -> Self {
+        match self.0.cmp(&right.0) {
+            Ordering::Less => *self,
+            Ordering::Greater => *right,
+            Ordering::Equal => MinCount(self.0, self.1 + right.1),
+        }
+    }
+}
+impl<T: Bounded> Associative for MinCount<T> {}
+impl<T: Bounded> Unital for MinCount<T> {
+    fn identity() -> Self { MinCount(T::max_value(), 0) }
+}
+impl<T: Bounded> Commutative for MinCount<T> {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MaxSubarraySum<T> {
+    pub sum: T,
+    pub prefix: T,
+    pub suffix: T,
+    pub best: T,
+}
+
+impl<T: Number + Ord> MaxSubarraySum<T> {
+    pub fn new(x: T) -> Self {
+        let y = max(x, T::zero());
+        MaxSubarraySum { sum: x, prefix: y, suffix: y, best: y }
+    }
+}
+impl<T: Number + Ord> Magma for MaxSubarraySum<T> {
+    fn op(&self, right: &Self) -> Self {
+        MaxSubarraySum {
+            sum: self.sum + right.sum,
+            prefix: max(self.prefix, self.sum + right.prefix),
+            suffix: max(right.suffix, self.suffix + right.sum),
+            best: max(max(self.best, right.best), self.suffix + right.prefix),
+        }
+    }
+}
+impl<T: Number + Ord> Associative for MaxSubarraySum<T> {}
+impl<T: Number + Ord> Unital for MaxSubarraySum<T> {
+    fn identity() -> Self { MaxSubarraySum::new(T::zero()) }
+}
+
+impl<A: Magma, B: Magma> Magma for (A, B) {
+    fn op(&self, right: &Self) -> Self { (self.0.op(&right.0), self.1.op(&right.1)) }
+}
+impl<A: Associative, B: Associative> Associative for (A, B) {}
+impl<A: Unital, B: Unital> Unital for (A, B) {
+    fn identity() -> Self { (A::identity(), B::identity()) }
+}
+impl<A: Commutative, B: Commutative> Commutative for (A, B) {}
+impl<A: Idempotent, B: Idempotent> Idempotent for (A, B) {}
+impl<A: Invertible, B: Invertible> Invertible for (A, B) {
+    fn inverse(&self) -> Self { (self.0.inverse(), self.1.inverse()) }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AddEffector<M: Scalar>(pub M::Value);
+
+impl<M: Scalar + Clone> Magma for AddEffector<M> where M::Value: Number {
+    fn op(&self, right: &Self) -> Self { AddEffector(self.0.wrapping_add(right.0)) }
+}
+impl<M: Scalar + Clone> Associative for AddEffector<M> where M::Value: Number {}
+impl<M: Scalar + Clone> Unital for AddEffector<M> where M::Value: Number {
+    fn identity() -> Self { AddEffector(M::Value::zero()) }
+}
+impl<M: Scalar + Clone> Commutative for AddEffector<M> where M::Value: Number {}
+impl<T: Number> Effector for AddEffector<Sum<T>> {
+    type Target = Sum<T>;
+    fn effect(&self, t: &Sum<T>, sz: usize) -> Sum<T> { Sum(t.0.wrapping_add(self.0.wrapping_mul(T::from_usize(sz)))) }
+}
+impl<T: Number + Bounded> Effector for AddEffector<Min<T>> {
+    type Target = Min<T>;
+    fn effect(&self, t: &Min<T>, _: usize) -> Min<T> {
+        if t.0 == T::max_value() { *t } else { Min(t.0 + self.0) }
+    }
+}
+impl<T: Number + Bounded> Effector for AddEffector<Max<T>> {
+    type Target = Max<T>;
+    fn effect(&self, t: &Max<T>, _: usize) -> Max<T> {
+        if t.0 == T::min_value() { *t } else { Max(t.0 + self.0) }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AssignEffector<M: Scalar>(pub Option<M::Value>);
+
+impl<M: Scalar + Clone> Magma for AssignEffector<M> {
+    fn op(&self, right: &Self) -> Self {
+        AssignEffector(if right.0.is_none() { self.0 } else { right.0 })
+    }
+}
+impl<M: Scalar + Clone> Associative for AssignEffector<M> {}
+impl<M: Scalar + Clone> Unital for AssignEffector<M> {
+    fn identity() -> Self { AssignEffector(None) }
+}
+impl<M: Scalar + Clone> Idempotent for AssignEffector<M> {}
+impl<T: Number> Effector for AssignEffector<Sum<T>> {
+    type Target = Sum<T>;
+    fn effect(&self, t: &Sum<T>, sz: usize) -> Sum<T> {
+        match self.0 {
+            Some(x) => Sum(x.wrapping_mul(T::from_usize(sz))),
+            None => *t,
+        }
+    }
+}
+impl<T: Bounded> Effector for AssignEffector<Min<T>> {
+    type Target = Min<T>;
+    fn effect(&self, t: &Min<T>, _: usize) -> Min<T> { Min(self.0.unwrap_or(t.0)) }
+}
+impl<T: Bounded> Effector for AssignEffector<Max<T>> {
+    type Target = Max<T>;
+    fn effect(&self, t: &Max<T>, _: usize) -> Max<T> { Max(self.0.unwrap_or(t.0)) }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AffineEffector<M: Scalar>(pub M::Value, pub M::Value);
+
+impl<M: Scalar + Clone> Magma for AffineEffector<M> where M::Value: Number {
+    fn op(&self, right: &Self) -> Self {
+        AffineEffector(right.0.wrapping_mul(self.0), right.0.wrapping_mul(self.1).wrapping_add(right.1))
+    }
+}
+impl<M: Scalar + Clone> Associative for AffineEffector<M> where M::Value: Number {}
+impl<M: Scalar + Clone> Unital for AffineEffector<M> where M::Value: Number {
+    fn identity() -> Self { AffineEffector(M::Value::one(), M::Value::zero()) }
+}
+impl<T: Number> Effector for AffineEffector<Sum<T>> {
+    type Target = Sum<T>;
+    fn effect(&self, t: &Sum<T>, sz: usize) -> Sum<T> {
+        Sum(self.0.wrapping_mul(t.0).wrapping_add(self.1.wrapping_mul(T::from_usize(sz))))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChminEffector<M: Scalar>(pub M::Value);
+
+impl<M: Scalar + Clone> Magma for ChminEffector<M> where M::Value: Bounded {
+    fn op(&self, right: &Self) -> Self { ChminEffector(min(self.0, right.0)) }
+}
+impl<M: Scalar + Clone> Associative for ChminEffector<M> where M::Value: Bounded {}
+impl<M: Scalar + Clone> Unital for ChminEffector<M> where M::Value: Bounded {
+    fn identity() -> Self { ChminEffector(M::Value::max_value()) }
+}
+impl<M: Scalar + Clone> Commutative for ChminEffector<M> where M::Value: Bounded {}
+impl<M: Scalar + Clone> Idempotent for ChminEffector<M> where M::Value: Bounded {}
+impl<T: Bounded> Effector for ChminEffector<Min<T>> {
+    type Target = Min<T>;
+    fn effect(&self, t: &Min<T>, _: usize) -> Min<T> { Min(min(t.0, self.0)) }
+}
+impl<T: Bounded> Effector for ChminEffector<Max<T>> {
+    type Target = Max<T>;
+    fn effect(&self, t: &Max<T>, _: usize) -> Max<T> { Max(min(t.0, self.0)) }
+}
+
+#[cfg(test)]
+mod monoids_test {
+    use algebra::*;
+    use algebra::monoids::*;
+    use segment_tree::segment_tree::SegmentTree;
+    use segment_tree::lazy_segment_tree::LazySegmentTree;
+    use segment_tree::persistent_lazy_segment_tree::PersistentLazySegmentTree;
+    use fenwick_tree::fenwick_tree::FenwickTree;
+    use static_fold::prefix_fold::PrefixFold;
+    use lctree::link_cut_forest::LinkCutForest;
+    use lctree::subtreenode::SubtreeNode;
+
+    fn fold_all<M: Monoid>(v: &[M]) -> M {
+        v.iter().fold(M::identity(), |acc, x| acc.op(x))
+    }
+
+    #[test]
+    fn monoid_test() {
+        let v: Vec<i64> = vec![3, -1, 4, -1, -5, 9, -2, 6];
+        let seg = SegmentTree::init(v.iter().map(|&x| (Min(x), (Max(x), MinCount(x, 1)))).collect());
+        let xs = SegmentTree::init(v.iter().map(|&x| (Xor(x), Gcd(x.abs()))).collect());
+        let ms = SegmentTree::init(v.iter().map(|&x| MaxSubarraySum::new(x)).collect());
+        for l in 0..v.len() + 1 {
+            for r in l..v.len() + 1 {
+                let (mn, (mx, mc)) = seg.fold(l..r);
+                assert_eq!(mn, fold_all(&v[l..r].iter().map(|&x| Min(x)).collect::<Vec<_>>()));
+                assert_eq!(mx.0, v[l..r].iter().cloned().max().unwrap_or(i64::MIN));
+                assert_eq!(mc.1, v[l..r].iter().filter(|&&x| x == mn.0).count());
+                let (x, g) = xs.fold(l..r);
+                assert_eq!(x.0, v[l..r].iter().fold(0, |a, b| a ^ b));
+                assert!(v[l..r].iter().all(|y| y % g.0 == 0));
+                let best = (l..r + 1).flat_map(|a| (a..r + 1).map(move |b| (a, b)))
+                    .map(|(a, b)| v[a..b].iter().sum::<i64>()).max().unwrap();
+                assert_eq!(ms.fold(l..r).best, best);
+            }
+        }
+        assert_eq!(Gcd(12u32).op(&Gcd(18)), Gcd(6));
+        assert_eq!(Gcd(-4).op(&Gcd(4)), Gcd(4));
+        assert_eq!(Gcd(4).op(&Gcd(-4)), Gcd(4));
+        assert_eq!(Affine(2, 1).op(&Affine(3, 4)).apply(5), 3 * (2 * 5 + 1) + 4);
+        assert_eq!(Sum(5i64).op(&Sum(5).inverse()), Sum::identity());
+    }
+
+    #[test]
+    fn unsigned_sum_test() {
+        let v: Vec<usize> = vec![3, 1, 4, 1, 5];
+        let mut fw = FenwickTree::<Sum<usize>>::from_vec(v.iter().map(|&x| Sum(x)).collect());
+        let pf = PrefixFold::<Sum<u32>>::init(v.iter().map(|&x| Sum(x as u32)).collect());
+        for l in 0..v.len() + 1 {
+            for r in l..v.len() + 1 {
+                assert_eq!(fw.fold(l..r).0, v[l..r].iter().sum::<usize>());
+                assert_eq!(pf.fold(l..r).0 as usize, v[l..r].iter().sum::<usize>());
+            }
+        }
+        fw.set(2, Sum(0));
+        assert_eq!(fw.fold(1..3).0, 1);
+        assert_eq!(Sum(u8::MAX).op(&Sum(2)), Sum(1));
+
+        let mut forest = LinkCutForest::<SubtreeNode<Sum<usize>>>::new();
+        let ids: Vec<_> = v.iter().map(|&x| forest.add_node(Sum(x))).collect();
+        for i in 1..ids.len() { forest.link(ids[i], ids[(i - 1) / 2]); }
+        assert_eq!(forest.subtree_fold(ids[1], ids[0]).0, 1 + 1 + 5);
+        assert_eq!(forest.subtree_fold(ids[0], ids[1]).0, 3 + 4);
+    }
+
+    #[test]
+    fn effector_test() {
+        let n = 10;
+        let mut sum = LazySegmentTree::<Sum<i64>, AffineEffector<Sum<i64>>>::init((0..n).map(|i| Sum(i as i64)).collect());
+        let mut add = LazySegmentTree::<Max<i64>, AddEffector<Max<i64>>>::init((0..n).map(|i| Max(i as i64)).collect());
+        let mut assign = LazySegmentTree::<Min<i64>, AssignEffector<Min<i64>>>::init((0..n).map(|i| Min(i as i64)).collect());
+        let mut chmin = LazySegmentTree::<Max<i64>, ChminEffector<Max<i64>>>::init((0..n).map(|i| Max(i as i64)).collect());
+        let mut v: Vec<i64> = (0..n as i64).collect();
+        let (mut va, mut vs, mut vc) = (v.clone(), v.clone(), v.clone());
+        for q in 0..50 {
+            let (l, r) = ((q * 7) % n, (q * 7) % n + (q * 3) % (n - (q * 7) % n) + 1);
+            let x = (q as i64 * 5) % 11 - 5;
+            sum.update_range(l, r, AffineEffector(2, x));
+            add.update_range(l, r, AddEffector(x));
+            assign.update_range(l, r, AssignEffector(Some(x)));
+            chmin.update_range(l, r, ChminEffector(x + 3));
+            for i in l..r {
+                v[i] = 2 * v[i] + x;
+                va[i] += x;
+                vs[i] = x;
+                vc[i] = vc[i].min(x + 3);
+            }
+            for a in 0..n {
+                for b in a + 1..n + 1 {
+                    assert_eq!(sum.fold(a..b).0, v[a..b].iter().sum::<i64>());
+                    assert_eq!(add.fold(a..b).0, *va[a..b].iter().max().unwrap());
+                    assert_eq!(assign.fold(a..b).0, *vs[a..b].iter().min().unwrap());
+                    assert_eq!(chmin.fold(a..b).0, *vc[a..b].iter().max().unwrap());
+                }
+            }
+            if v.iter().any(|x| x.abs() > 1 << 40) {
+                for (i, y) in v.iter_mut().enumerate() {
+                    *y = 0;
+                    sum.set(i, Sum(0));
+                }
+            }
+        }
+        let seg = PersistentLazySegmentTree::<Sum<u64>, AssignEffector<Sum<u64>>>::new(4)
+            .effect_range(1, 3, AssignEffector(Some(7)));
+        assert_eq!(seg.fold(..).0, 14);
+
+        let n = 6;
+        let mut add = LazySegmentTree::<Sum<u8>, AddEffector<Sum<u8>>>::init((0..n).map(|i| Sum(250 + i as u8)).collect());
+        let mut aff = LazySegmentTree::<Sum<u8>, AffineEffector<Sum<u8>>>::init((0..n).map(|i| Sum(250 + i as u8)).collect());
+        let mut asg = LazySegmentTree::<Sum<u8>, AssignEffector<Sum<u8>>>::init((0..n).map(|i| Sum(250 + i as u8)).collect());
+        add.update_range(0, n, AddEffector(200));
+        add.update_range(1, 4, AddEffector(100));
+        aff.update_range(0, n, AffineEffector(7, 200));
+        aff.update_range(1, 4, AffineEffector(3, 100));
+        asg.update_range(0, n, AssignEffector(Some(200)));
+        let fold = |v: &[u8]| v.iter().fold(0u8, |a, &b| a.wrapping_add(b));
+        let va: Vec<u8> = (0..n).map(|i| (250 + i as u8).wrapping_add(if (1..4).contains(&i) { 44 } else { 200 })).collect();
+        let vf: Vec<u8> = (0..n).map(|i| {
+            let x = (250 + i as u8).wrapping_mul(7).wrapping_add(200);
+            if (1..4).contains(&i) { x.wrapping_mul(3).wrapping_add(100) } else { x }
+        }).collect();
+        for a in 0..n + 1 {
+            for b in a..n + 1 {
+                assert_eq!(add.fold(a..b).0, fold(&va[a..b]));
+                assert_eq!(aff.fold(a..b).0, fold(&vf[a..b]));
+                assert_eq!(asg.fold(a..b).0, (200 * (b - a) % 256) as u8);
+            }
+        }
+    }
+}
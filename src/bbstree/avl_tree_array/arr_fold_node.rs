@@ -13,8 +13,8 @@ pub struct ArrFoldNode<T: Monoid> {
 impl<T: Monoid> ArrFoldNode<T> {
     pub fn new(val: T) -> Self {
         Self {
-            val: val,
-            fold: T::identity(),
+            val: val.clone(),
+            fold: val,
             size: 1,
             height: 1,
             child: [ None, None ],
@@ -46,6 +46,11 @@ impl<T: Monoid> Node for ArrFoldNode<T> {
     fn val_mut(&mut self) -> &mut Self::Value { &mut self.val }
 }
 
+impl<T: Monoid> BuildNode for ArrFoldNode<T> {
+    fn new(val: T) -> Self { ArrFoldNode::new(val) }
+    fn into_val(self) -> T { self.val }
+}
+
 impl<T: Monoid> SizeNode for ArrFoldNode<T> {
     fn size(&self) -> usize { self.size }
 }
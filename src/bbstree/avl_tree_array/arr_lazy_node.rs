@@ -0,0 +1,83 @@
+use algebra::*;
+use bbstree::node_traits::*;
+use std::cmp::max;
+
+pub struct ArrLazyNode<T: Monoid, E: Effector<Target=T>> {
+    val: T,
+    fold: T,
+    eff: E,
+    size: usize,
+    height: isize,
+    child: [Link<ArrLazyNode<T, E>>; 2]
+} 
+
+impl<T: Monoid, E: Effector<Target=T>> ArrLazyNode<T, E> {
+    pub fn new(val: T) -> Self {
+        Self {
+            val: val.clone(),
+            fold: val,
+            eff: E::identity(),
+            size: 1,
+            height: 1,
+            child: [ None, None ],
+        }
+    }
+}
+
+impl<T: Monoid, E: Effector<Target=T>> Node for ArrLazyNode<T, E> {
+    type Value = T;
+    fn fix(&mut self) {
+        self.size = size(&self.child[0]) + size(&self.child[1]) + 1;
+        self.height = max(height(&self.child[0]), height(&self.child[1])) + 1;
+        let lf = match self.child[0] { Some(ref node) => node.fold().clone(), None => T::identity() };
+        let rf = match self.child[1] { Some(ref node) => node.fold().clone(), None => T::identity() };
+        self.fold = lf.op(&self.val).op(&rf);
+    }
+    fn child(&mut self, dir: usize) -> &mut Link<Self> { &mut self.child[dir] } 
+    fn child_imut(&self, dir: usize) -> &Link<Self> { &self.child[dir] } 
+    fn cut(&mut self, dir: usize) -> Link<Self> {
+        self.push();
+        let nn = self.child[dir].take();
+        self.fix();
+        nn
+    }
+    fn set(&mut self, dir_node: Link<Self>, dir: usize) {
+        self.push();
+        self.child[dir] = dir_node;
+        self.fix();
+    }
+    fn val(&self) -> &Self::Value { &self.val }
+    fn val_mut(&mut self) -> &mut Self::Value { &mut self.val }
+    fn push(&mut self) {
+        let eff = std::mem::replace(&mut self.eff, E::identity());
+        for ch in self.child.iter_mut() {
+            if let Some(ref mut node) = *ch { node.effect(&eff); }
+        }
+    }
+}
+
+impl<T: Monoid, E: Effector<Target=T>> BuildNode for ArrLazyNode<T, E> {
+    fn new(val: T) -> Self { ArrLazyNode::new(val) }
+    fn into_val(self) -> T { self.val }
+}
+
+impl<T: Monoid, E: Effector<Target=T>> SizeNode for ArrLazyNode<T, E> {
+    fn size(&self) -> usize { self.size }
+}
+
+impl<T: Monoid, E: Effector<Target=T>> HeightNode for ArrLazyNode<T, E> {
+    fn height(&self) -> isize { self.height }
+}
+
+impl<T: Monoid, E: Effector<Target=T>> FoldNode for ArrLazyNode<T, E> {
+    fn fold(&self) -> &T { &self.fold } 
+}
+
+impl<T: Monoid, E: Effector<Target=T>> EffectNode for ArrLazyNode<T, E> {
+    type Effector = E;
+    fn effect(&mut self, e: &E) {
+        self.val = e.effect(&self.val, 1);
+        self.fold = e.effect(&self.fold, self.size);
+        self.eff = self.eff.op(e);
+    }
+}
@@ -11,7 +11,7 @@ pub struct ArrNode<T> {
 impl<T> ArrNode<T> {
     pub fn new(val: T) -> Self {
         Self {
-            val: val,
+            val,
             size: 1,
             height: 1,
             child: [ None, None ],
@@ -40,6 +40,11 @@ impl<T> Node for ArrNode<T> {
     fn val_mut(&mut self) -> &mut Self::Value { &mut self.val }
 }
 
+impl<T> BuildNode for ArrNode<T> {
+    fn new(val: T) -> Self { ArrNode::new(val) }
+    fn into_val(self) -> T { self.val }
+}
+
 impl<T> SizeNode for ArrNode<T> {
     fn size(&self) -> usize { self.size }
 }
@@ -0,0 +1,94 @@
+use algebra::*;
+use bbstree::node_traits::*;
+use std::cmp::max;
+
+pub struct ArrRevNode<T: Monoid> {
+    val: T,
+    fold: T,
+    rev_fold: T,
+    rev: bool,
+    size: usize,
+    height: isize,
+    child: [Link<ArrRevNode<T>>; 2]
+} 
+
+impl<T: Monoid> ArrRevNode<T> {
+    pub fn new(val: T) -> Self {
+        Self {
+            val: val.clone(),
+            fold: val.clone(),
+            rev_fold: val,
+            rev: false,
+            size: 1,
+            height: 1,
+            child: [ None, None ],
+        }
+    }
+}
+
+impl<T: Monoid> Node for ArrRevNode<T> {
+    type Value = T;
+    fn fix(&mut self) {
+        self.size = size(&self.child[0]) + size(&self.child[1]) + 1;
+        self.height = max(height(&self.child[0]), height(&self.child[1])) + 1;
+        self.fold = self.val.clone();
+        self.rev_fold = self.val.clone();
+        if let Some(ref left) = self.child[0] {
+            self.fold = left.fold().op(&self.fold);
+            self.rev_fold = self.rev_fold.op(left.rev_fold());
+        }
+        if let Some(ref right) = self.child[1] {
+            self.fold = self.fold.op(right.fold());
+            self.rev_fold = right.rev_fold().op(&self.rev_fold);
+        }
+    }
+    fn child(&mut self, dir: usize) -> &mut Link<Self> { &mut self.child[dir] } 
+    fn child_imut(&self, dir: usize) -> &Link<Self> { &self.child[dir] } 
+    fn cut(&mut self, dir: usize) -> Link<Self> {
+        self.push();
+        let nn = self.child[dir].take();
+        self.fix();
+        nn
+    }
+    fn set(&mut self, dir_node: Link<Self>, dir: usize) {
+        self.push();
+        self.child[dir] = dir_node;
+        self.fix();
+    }
+    fn val(&self) -> &Self::Value { &self.val }
+    fn val_mut(&mut self) -> &mut Self::Value { &mut self.val }
+    fn push(&mut self) {
+        if self.rev {
+            for ch in self.child.iter_mut() {
+                if let Some(ref mut node) = *ch { node.reverse(); }
+            }
+            self.rev = false;
+        }
+    }
+}
+
+impl<T: Monoid> BuildNode for ArrRevNode<T> {
+    fn new(val: T) -> Self { ArrRevNode::new(val) }
+    fn into_val(self) -> T { self.val }
+}
+
+impl<T: Monoid> SizeNode for ArrRevNode<T> {
+    fn size(&self) -> usize { self.size }
+}
+
+impl<T: Monoid> HeightNode for ArrRevNode<T> {
+    fn height(&self) -> isize { self.height }
+}
+
+impl<T: Monoid> FoldNode for ArrRevNode<T> {
+    fn fold(&self) -> &T { &self.fold } 
+}
+
+impl<T: Monoid> ReversibleNode for ArrRevNode<T> {
+    fn reverse(&mut self) {
+        self.child.swap(0, 1);
+        std::mem::swap(&mut self.fold, &mut self.rev_fold);
+        self.rev ^= true;
+    }
+    fn rev_fold(&self) -> &T { &self.rev_fold }
+}
@@ -1,5 +1,6 @@
 use bbstree::node_traits::*;
 use algebra::{ Monoid, Unital };
+use std::iter::FromIterator;
 
 pub trait AVLSizeNode: Node + SizeNode + HeightNode {}
 
@@ -64,7 +65,7 @@ fn merge_dir<N: AVLSizeNode>(dst: Box<N>, mut root: Box<N>, src: Link<N>, dir: u
     }
 }
 
-fn merge<N: AVLSizeNode>(left: Link<N>, right: Link<N>) -> Link<N> {
+pub(crate) fn merge<N: AVLSizeNode>(left: Link<N>, right: Link<N>) -> Link<N> {
     match left {
         Some(ln) => {
             match right {
@@ -85,7 +86,7 @@ fn merge<N: AVLSizeNode>(left: Link<N>, right: Link<N>) -> Link<N> {
     } 
 }
 
-fn split<N: AVLSizeNode>(node: Box<N>, i: usize) -> (Link<N>, Link<N>) {
+pub(crate) fn split<N: AVLSizeNode>(node: Box<N>, i: usize) -> (Link<N>, Link<N>) {
     if i == node.size() { return (Some(node), None); }
     let (node, left) = cut(node, 0);
     let (node, right) = cut(node, 1);
@@ -110,18 +111,22 @@ fn split<N: AVLSizeNode>(node: Box<N>, i: usize) -> (Link<N>, Link<N>) {
     }
 }
 
-fn at<N: AVLSizeNode>(node: &Box<N>, i: usize) -> &N::Value {
-    if size(&node.child_imut(0)) == i {
+fn at<N: AVLSizeNode>(node: &mut N, i: usize) -> &N::Value {
+    node.push();
+    let sz = size(node.child_imut(0));
+    if sz == i {
         node.val()
     }
-    else if size(&node.child_imut(0)) < i {
-        at(node.child_imut(1).as_ref().unwrap(), i - size(&node.child_imut(0)) - 1) } else {
-        at(node.child_imut(0).as_ref().unwrap(), i)
+    else if sz < i {
+        at(node.child(1).as_deref_mut().unwrap(), i - sz - 1)
+    } else {
+        at(node.child(0).as_deref_mut().unwrap(), i)
     }
 }
 
 fn at_set<N: AVLSizeNode>(node: &mut Box<N>, i: usize, val: N::Value) {
-    let sz = size(&node.child(0));
+    node.push();
+    let sz = size(node.child(0));
     if sz == i {
         *node.as_mut().val_mut() = val
     }
@@ -133,6 +138,36 @@ fn at_set<N: AVLSizeNode>(node: &mut Box<N>, i: usize, val: N::Value) {
     node.fix()
 }
 
+fn split3<N: AVLSizeNode>(node: Link<N>, l: usize, r: usize) -> (Link<N>, Link<N>, Link<N>) {
+    let (left, right) = match node {
+        Some(node) => split(node, r),
+        None => (None, None),
+    };
+    let (left, center) = match left {
+        Some(node) => split(node, l),
+        None => (None, None),
+    };
+    (left, center, right)
+}
+
+fn build<N: AVLSizeNode + BuildNode, I: Iterator<Item=N::Value>>(n: usize, iter: &mut I) -> Link<N> {
+    if n == 0 { return None; }
+    let left = build(n >> 1, iter);
+    let mut node = Box::new(N::new(iter.next().unwrap()));
+    let right = build(n - (n >> 1) - 1, iter);
+    node.set(left, 0);
+    node.set(right, 1);
+    Some(node)
+}
+
+fn push_all<N: AVLSizeNode>(link: &mut Link<N>) {
+    if let Some(ref mut node) = *link {
+        node.push();
+        push_all(node.child(0));
+        push_all(node.child(1));
+    }
+}
+
 pub struct AVLTreeArray<N: AVLSizeNode> {
     root: Link<N>,
 }
@@ -156,14 +191,94 @@ impl<N: AVLSizeNode> AVLTreeArray<N> {
             None => ( Self { root: None }, Self { root: None } )
         }
     }
-    pub fn at(&self, i: usize) -> &N::Value {
+    pub fn at(&mut self, i: usize) -> &N::Value {
         assert!(i < size(&self.root), "at(): out of range");
-        at(self.root.as_ref().unwrap(), i)
+        at(self.root.as_deref_mut().unwrap(), i)
     }
     pub fn at_set(&mut self, i: usize, val: N::Value) {
         assert!(i < size(&self.root), "at_set(): out of range");
         at_set(self.root.as_mut().unwrap(), i, val);
     }
+    pub fn len(&self) -> usize {
+        size(&self.root)
+    }
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+    pub fn iter(&mut self) -> Iter<'_, N> {
+        push_all(&mut self.root);
+        let mut iter = Iter { stack: Vec::new() };
+        iter.push_left(self.root.as_deref());
+        iter
+    }
+}
+
+pub struct Iter<'a, N: AVLSizeNode + 'a> {
+    stack: Vec<&'a N>,
+}
+
+impl<'a, N: AVLSizeNode> Iter<'a, N> {
+    fn push_left(&mut self, mut node: Option<&'a N>) {
+        while let Some(n) = node {
+            self.stack.push(n);
+            node = n.child_imut(0).as_deref();
+        }
+    }
+}
+
+impl<'a, N: AVLSizeNode> Iterator for Iter<'a, N> {
+    type Item = &'a N::Value;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.stack.pop().map(|node| {
+            self.push_left(node.child_imut(1).as_deref());
+            node.val()
+        })
+    }
+}
+
+impl<N: AVLSizeNode + BuildNode> AVLTreeArray<N> {
+    pub fn from_slice(vals: &[N::Value]) -> Self where N::Value: Clone {
+        Self { root: build(vals.len(), &mut vals.iter().cloned()) }
+    }
+    pub fn insert(&mut self, i: usize, val: N::Value) {
+        assert!(i <= size(&self.root), "insert(): out of range");
+        let (left, right) = match self.root.take() {
+            Some(node) => split(node, i),
+            None => (None, None),
+        };
+        self.root = merge(merge(left, Some(Box::new(N::new(val)))), right);
+    }
+    pub fn remove(&mut self, i: usize) -> N::Value {
+        assert!(i < size(&self.root), "remove(): out of range");
+        let (left, center, right) = split3(self.root.take(), i, i + 1);
+        self.root = merge(left, right);
+        center.unwrap().into_val()
+    }
+    pub fn push_back(&mut self, val: N::Value) {
+        let i = self.len();
+        self.insert(i, val);
+    }
+    pub fn push_front(&mut self, val: N::Value) {
+        self.insert(0, val);
+    }
+    pub fn pop_back(&mut self) -> Option<N::Value> {
+        if self.is_empty() { None }
+        else {
+            let i = self.len() - 1;
+            Some(self.remove(i))
+        }
+    }
+    pub fn pop_front(&mut self) -> Option<N::Value> {
+        if self.is_empty() { None }
+        else { Some(self.remove(0)) }
+    }
+}
+
+impl<N: AVLSizeNode + BuildNode> FromIterator<N::Value> for AVLTreeArray<N> {
+    fn from_iter<I: IntoIterator<Item=N::Value>>(iter: I) -> Self {
+        let vals: Vec<_> = iter.into_iter().collect();
+        Self { root: build(vals.len(), &mut vals.into_iter()) }
+    }
 }
 
 impl<N: AVLSizeNode + FoldNode> AVLTreeArray<N> where N::Value: Monoid {
@@ -173,6 +288,34 @@ impl<N: AVLSizeNode + FoldNode> AVLTreeArray<N> where N::Value: Monoid {
             None => <N::Value as Unital>::identity(),
         }
     }
+    pub fn fold_range(&mut self, l: usize, r: usize) -> N::Value {
+        assert!(l <= r && r <= size(&self.root), "fold_range(): out of range");
+        let (left, center, right) = split3(self.root.take(), l, r);
+        let res = match center {
+            Some(ref node) => node.fold().clone(),
+            None => <N::Value as Unital>::identity(),
+        };
+        self.root = merge(merge(left, center), right);
+        res
+    }
+}
+
+impl<N: AVLSizeNode + EffectNode> AVLTreeArray<N> where N::Value: Monoid {
+    pub fn effect_range(&mut self, l: usize, r: usize, e: N::Effector) {
+        assert!(l <= r && r <= size(&self.root), "effect_range(): out of range");
+        let (left, mut center, right) = split3(self.root.take(), l, r);
+        if let Some(ref mut node) = center { node.effect(&e); }
+        self.root = merge(merge(left, center), right);
+    }
+}
+
+impl<N: AVLSizeNode + ReversibleNode> AVLTreeArray<N> where N::Value: Monoid {
+    pub fn reverse_range(&mut self, l: usize, r: usize) {
+        assert!(l <= r && r <= size(&self.root), "reverse_range(): out of range");
+        let (left, mut center, right) = split3(self.root.take(), l, r);
+        if let Some(ref mut node) = center { node.reverse(); }
+        self.root = merge(merge(left, center), right);
+    }
 }
 
 #[test]
@@ -184,7 +327,7 @@ fn avlarray_test() {
     let arr = arr.merge(AVLTreeArray::new(ArrNode::new(2)));
     let arr = arr.merge(AVLTreeArray::new(ArrNode::new(3)));
     let arr = arr.merge(AVLTreeArray::new(ArrNode::new(4)));
-    let arr = arr.merge(AVLTreeArray::new(ArrNode::new(5)));
+    let mut arr = arr.merge(AVLTreeArray::new(ArrNode::new(5)));
     assert!(*arr.at(0) == 0);
     assert!(*arr.at(1) == 1);
     assert!(*arr.at(2) == 2);
@@ -195,19 +338,8 @@ fn avlarray_test() {
 
 #[cfg(test)]
 mod avlrsq_test {
-    use algebra::*;
 
-    #[derive(Clone)]
-    struct Am(usize);
-
-    impl Magma for Am {
-        fn op(&self, right: &Self) -> Self { Am(self.0 + right.0) }
-    }
-    impl Associative for Am {}
-
-    impl Unital for Am {
-        fn identity() -> Self { Am(0) }
-    }
+    monoid!(Am, usize, 0, |a, b| a + b);
 
     #[test]
     fn avlrsq_test()  {
@@ -231,3 +363,152 @@ mod avlrsq_test {
         }
     }
 }
+
+#[cfg(test)]
+mod avl_lazy_test {
+    use bbstree::avl_tree_array::{ AVLTreeArray, ArrLazyNode };
+
+    monoid!(#[derive(Debug)] Sm, usize, 0, |a, b| a + b);
+
+    effector!(#[derive(Debug)] Aq, usize, 0, |a, b| a + b, Sm, |e, t, s| Sm(t.0 + e * s));
+
+    #[test]
+    fn rsq_raq_test() {
+        let mut arr = AVLTreeArray::none();
+        for i in 0..10 {
+            arr = arr.merge(AVLTreeArray::new(ArrLazyNode::<Sm, Aq>::new(Sm(i))));
+        }
+        let mut model: Vec<usize> = (0..10).collect();
+        let query = [(0, 10, 1), (2, 5, 3), (4, 9, 2), (7, 8, 10), (0, 1, 5)];
+        for &(l, r, x) in query.iter() {
+            arr.effect_range(l, r, Aq(x));
+            for v in model[l..r].iter_mut() { *v += x; }
+            for a in 0..11 {
+                for b in a..11 {
+                    assert_eq!(arr.fold_range(a, b).0, model[a..b].iter().sum::<usize>());
+                }
+            }
+        }
+        let (left, right) = arr.split(4);
+        let mut arr = right.merge(left);
+        model.rotate_left(4);
+        arr.effect_range(3, 7, Aq(100));
+        for v in model[3..7].iter_mut() { *v += 100; }
+        for (i, v) in model.iter().enumerate() {
+            assert_eq!(arr.at(i).0, *v);
+        }
+        assert!(arr.iter().map(|x| x.0).eq(model.iter().cloned()));
+        arr.at_set(5, Sm(0));
+        model[5] = 0;
+        assert_eq!(arr.fold().0, model.iter().sum::<usize>());
+        assert_eq!(arr.fold_range(2, 8).0, model[2..8].iter().sum::<usize>());
+    }
+
+    #[test]
+    fn at_after_effect_test() {
+        let mut arr: AVLTreeArray<ArrLazyNode<Sm, Aq>> = (0..8).map(Sm).collect();
+        arr.effect_range(0, 8, Aq(10));
+        arr.effect_range(2, 5, Aq(1));
+        assert_eq!(arr.at(0).0, 10);
+        assert_eq!(arr.at(3).0, 14);
+        assert_eq!(arr.at(7).0, 17);
+    }
+}
+
+#[cfg(test)]
+mod avl_reverse_test {
+    use bbstree::avl_tree_array::{ AVLTreeArray, ArrRevNode };
+
+    monoid!(#[derive(Debug)] Cat, Vec<usize>, Vec::new(), |a, b| [a, b].concat());
+
+    #[test]
+    fn reverse_range_test() {
+        let mut arr = AVLTreeArray::none();
+        for i in 0..12 {
+            arr = arr.merge(AVLTreeArray::new(ArrRevNode::new(Cat(vec![i]))));
+        }
+        let mut model: Vec<usize> = (0..12).collect();
+        let query = [(0, 12), (2, 7), (5, 11), (3, 4), (0, 6), (1, 12), (4, 9)];
+        for &(l, r) in query.iter() {
+            arr.reverse_range(l, r);
+            model[l..r].reverse();
+            assert_eq!(arr.fold().0, model);
+            for a in 0..13 {
+                for b in a..13 {
+                    assert_eq!(arr.fold_range(a, b).0, &model[a..b]);
+                }
+            }
+        }
+        let (left, right) = arr.split(5);
+        let mut arr = right.merge(left);
+        model.rotate_left(5);
+        arr.reverse_range(2, 10);
+        model[2..10].reverse();
+        for (i, v) in model.iter().enumerate() {
+            assert_eq!(arr.at(i).0, vec![*v]);
+        }
+    }
+
+    #[test]
+    fn read_after_reverse_test() {
+        let mut arr: AVLTreeArray<ArrRevNode<Cat>> = (0..6).map(|i| Cat(vec![i])).collect();
+        arr.reverse_range(0, 6);
+        assert!(arr.iter().map(|x| x.0[0]).eq((0..6).rev()));
+        assert_eq!(arr.at(0).0, vec![5]);
+        arr.reverse_range(1, 4);
+        assert_eq!(arr.at(1).0, vec![2]);
+        assert!(arr.iter().map(|x| x.0[0]).eq(vec![5, 2, 3, 4, 1, 0]));
+    }
+}
+
+#[cfg(test)]
+mod avl_vec_test {
+    use bbstree::avl_tree_array::{ AVLTreeArray, ArrNode };
+    use algebra::laws::Rng;
+
+    #[test]
+    fn from_slice_test() {
+        let vals: Vec<usize> = (0..100).collect();
+        let mut arr = AVLTreeArray::<ArrNode<usize>>::from_slice(&vals);
+        assert_eq!(arr.len(), 100);
+        assert!(arr.iter().cloned().eq(0..100));
+        let mut arr: AVLTreeArray<ArrNode<usize>> = (0..7).collect();
+        assert!(arr.iter().cloned().eq(0..7));
+        let mut arr: AVLTreeArray<ArrNode<usize>> = Vec::new().into_iter().collect();
+        assert!(arr.is_empty());
+        assert_eq!(arr.iter().next(), None);
+    }
+
+    #[test]
+    fn insert_remove_test() {
+        let mut arr: AVLTreeArray<ArrNode<usize>> = AVLTreeArray::none();
+        let mut model = Vec::new();
+        let mut rng = Rng::new(1);
+        for step in 0..2000 {
+            match rng.gen_index(6) {
+                0 | 1 => {
+                    let i = rng.gen_index(model.len() + 1);
+                    arr.insert(i, step);
+                    model.insert(i, step);
+                }
+                2 if !model.is_empty() => {
+                    let i = rng.gen_index(model.len());
+                    assert_eq!(arr.remove(i), model.remove(i));
+                }
+                3 => {
+                    arr.push_back(step);
+                    model.push(step);
+                }
+                4 => {
+                    let v = if model.is_empty() { None } else { Some(model.remove(0)) };
+                    assert_eq!(arr.pop_front(), v);
+                }
+                _ => {
+                    assert_eq!(arr.pop_back(), model.pop());
+                }
+            }
+            assert_eq!(arr.len(), model.len());
+        }
+        assert!(arr.iter().eq(model.iter()));
+    }
+}
@@ -1,8 +1,12 @@
 pub mod arr_node;
 pub mod arr_fold_node;
+pub mod arr_lazy_node;
+pub mod arr_rev_node;
+#[allow(clippy::module_inception)]
 pub mod avl_tree_array;
 
 pub use self::arr_node::ArrNode;
 pub use self::arr_fold_node::ArrFoldNode;
+pub use self::arr_lazy_node::ArrLazyNode;
+pub use self::arr_rev_node::ArrRevNode;
 pub use self::avl_tree_array::AVLTreeArray;
-
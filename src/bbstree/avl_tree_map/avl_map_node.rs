@@ -0,0 +1,59 @@
+use bbstree::node_traits::*;
+use std::cmp::max;
+
+pub struct AVLMapNode<K: Ord, V> {
+    val: (K, V),
+    size: usize,
+    height: isize,
+    child: [Link<AVLMapNode<K, V>>; 2]
+} 
+
+impl<K: Ord, V> AVLMapNode<K, V> {
+    pub fn new(key: K, val: V) -> Self {
+        Self {
+            val: (key, val),
+            size: 1,
+            height: 1,
+            child: [ None, None ],
+        }
+    }
+}
+
+impl<K: Ord, V> Node for AVLMapNode<K, V> {
+    type Value = (K, V);
+    fn fix(&mut self) {
+        self.size = size(&self.child[0]) + size(&self.child[1]) + 1;
+        self.height = max(height(&self.child[0]), height(&self.child[1])) + 1;
+    }
+    fn child(&mut self, dir: usize) -> &mut Link<Self> { &mut self.child[dir] } 
+    fn child_imut(&self, dir: usize) -> &Link<Self> { &self.child[dir] } 
+    fn cut(&mut self, dir: usize) -> Link<Self> {
+        let nn = self.child[dir].take();
+        self.fix();
+        nn
+    }
+    fn set(&mut self, dir_node: Link<Self>, dir: usize) {
+        self.child[dir] = dir_node;
+        self.fix();
+    }
+    fn val(&self) -> &Self::Value { &self.val }
+    fn val_mut(&mut self) -> &mut Self::Value { &mut self.val }
+}
+
+impl<K: Ord, V> BuildNode for AVLMapNode<K, V> {
+    fn new(val: (K, V)) -> Self { AVLMapNode::new(val.0, val.1) }
+    fn into_val(self) -> (K, V) { self.val }
+}
+
+impl<K: Ord, V> SizeNode for AVLMapNode<K, V> {
+    fn size(&self) -> usize { self.size }
+}
+
+impl<K: Ord, V> HeightNode for AVLMapNode<K, V> {
+    fn height(&self) -> isize { self.height }
+}
+
+impl<K: Ord, V> MapNode for AVLMapNode<K, V> {
+    type Key = K;
+    fn key(&self) -> &K { &self.val.0 }
+}
@@ -0,0 +1,251 @@
+use bbstree::node_traits::*;
+use bbstree::avl_tree_array::avl_tree_array::{ merge, split, AVLSizeNode };
+use bbstree::avl_tree_map::AVLMapNode;
+use std::cmp::Ordering;
+use std::marker::PhantomData;
+use std::ops::{ Bound, RangeBounds };
+
+pub trait AVLMapSizeNode: AVLSizeNode + MapNode + BuildNode {}
+
+impl<N: AVLSizeNode + MapNode + BuildNode> AVLMapSizeNode for N {}
+
+// the number of keys less than (or equal to, if `inclusive`) `key`
+fn count<N: AVLMapSizeNode>(link: &Link<N>, key: &N::Key, inclusive: bool) -> usize {
+    let mut res = 0;
+    let mut cur = link;
+    while let Some(ref node) = *cur {
+        let go_right = match node.key().cmp(key) {
+            Ordering::Less => true,
+            Ordering::Equal => inclusive,
+            Ordering::Greater => false,
+        };
+        if go_right {
+            res += size(node.child_imut(0)) + 1;
+            cur = node.child_imut(1);
+        }
+        else {
+            cur = node.child_imut(0);
+        }
+    }
+    res
+}
+
+fn find_mut<'a, N: AVLMapSizeNode>(link: &'a mut Link<N>, key: &N::Key) -> Option<&'a mut N> {
+    match *link {
+        Some(ref mut node) => {
+            match node.key().cmp(key) {
+                Ordering::Less => find_mut(node.child(1), key),
+                Ordering::Greater => find_mut(node.child(0), key),
+                Ordering::Equal => Some(node),
+            }
+        }
+        None => None,
+    }
+}
+
+fn nth<N: AVLMapSizeNode>(link: &Link<N>, mut i: usize) -> Option<&N> {
+    let mut cur = link;
+    while let Some(ref node) = *cur {
+        let sz = size(node.child_imut(0));
+        if i < sz { cur = node.child_imut(0); }
+        else if i == sz { return Some(node); }
+        else {
+            i -= sz + 1;
+            cur = node.child_imut(1);
+        }
+    }
+    None
+}
+
+fn split_at<N: AVLMapSizeNode>(link: Link<N>, i: usize) -> (Link<N>, Link<N>) {
+    match link {
+        Some(node) => split(node, i),
+        None => (None, None),
+    }
+}
+
+pub struct AVLTreeMap<K: Ord, V, N: AVLMapSizeNode<Key=K, Value=(K, V)> = AVLMapNode<K, V>> {
+    root: Link<N>,
+}
+
+impl<K: Ord, V> AVLTreeMap<K, V> {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self { root: None }
+    }
+}
+
+impl<K: Ord, V, N: AVLMapSizeNode<Key=K, Value=(K, V)>> AVLTreeMap<K, V, N> {
+    pub fn none() -> Self {
+        Self { root: None }
+    }
+    pub fn len(&self) -> usize {
+        size(&self.root)
+    }
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+    pub fn insert(&mut self, key: K, val: V) -> Option<V> {
+        if let Some(node) = find_mut(&mut self.root, &key) {
+            return Some(std::mem::replace(&mut node.val_mut().1, val));
+        }
+        let i = count(&self.root, &key, false);
+        let (left, right) = split_at(self.root.take(), i);
+        self.root = merge(merge(left, Some(Box::new(N::new((key, val))))), right);
+        None
+    }
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let i = count(&self.root, key, false);
+        match nth(&self.root, i) {
+            Some(node) if node.key() == key => {}
+            _ => return None,
+        }
+        let (left, right) = split_at(self.root.take(), i + 1);
+        let (left, center) = split_at(left, i);
+        self.root = merge(left, right);
+        center.map(|node| node.into_val().1)
+    }
+    pub fn get(&self, key: &K) -> Option<&V> {
+        let mut cur = &self.root;
+        while let Some(ref node) = *cur {
+            match node.key().cmp(key) {
+                Ordering::Less => cur = node.child_imut(1),
+                Ordering::Greater => cur = node.child_imut(0),
+                Ordering::Equal => return Some(&node.val().1),
+            }
+        }
+        None
+    }
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        find_mut(&mut self.root, key).map(|node| &mut node.val_mut().1)
+    }
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+    pub fn lower_bound(&self, key: &K) -> Option<(&K, &V)> {
+        self.nth(count(&self.root, key, false))
+    }
+    pub fn upper_bound(&self, key: &K) -> Option<(&K, &V)> {
+        self.nth(count(&self.root, key, true))
+    }
+    pub fn rank(&self, key: &K) -> usize {
+        count(&self.root, key, false)
+    }
+    pub fn nth(&self, i: usize) -> Option<(&K, &V)> {
+        nth(&self.root, i).map(|node| (&node.val().0, &node.val().1))
+    }
+    pub fn iter(&self) -> Iter<'_, K, V, N> {
+        Iter::new(&self.root, 0, self.len())
+    }
+    pub fn range<R: RangeBounds<K>>(&self, range: R) -> Iter<'_, K, V, N> {
+        let l = match range.start_bound() {
+            Bound::Included(key) => count(&self.root, key, false),
+            Bound::Excluded(key) => count(&self.root, key, true),
+            Bound::Unbounded => 0,
+        };
+        let r = match range.end_bound() {
+            Bound::Included(key) => count(&self.root, key, true),
+            Bound::Excluded(key) => count(&self.root, key, false),
+            Bound::Unbounded => self.len(),
+        };
+        Iter::new(&self.root, l, if l < r { r } else { l })
+    }
+}
+
+pub struct Iter<'a, K: Ord + 'a, V: 'a, N: AVLMapSizeNode<Key=K, Value=(K, V)> + 'a = AVLMapNode<K, V>> {
+    stack: Vec<&'a N>,
+    remain: usize,
+    _p: PhantomData<(&'a K, &'a V)>,
+}
+
+impl<'a, K: Ord, V, N: AVLMapSizeNode<Key=K, Value=(K, V)>> Iter<'a, K, V, N> {
+    fn new(root: &'a Link<N>, mut l: usize, r: usize) -> Self {
+        let remain = r - l;
+        let mut stack = Vec::new();
+        let mut cur = root;
+        while let Some(ref node) = *cur {
+            let sz = size(node.child_imut(0));
+            if l < sz {
+                stack.push(&**node);
+                cur = node.child_imut(0);
+            }
+            else if l == sz {
+                stack.push(&**node);
+                break;
+            }
+            else {
+                l -= sz + 1;
+                cur = node.child_imut(1);
+            }
+        }
+        Iter { stack, remain, _p: PhantomData }
+    }
+}
+
+impl<'a, K: Ord, V, N: AVLMapSizeNode<Key=K, Value=(K, V)>> Iterator for Iter<'a, K, V, N> {
+    type Item = (&'a K, &'a V);
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remain == 0 { return None; }
+        self.remain -= 1;
+        self.stack.pop().map(|node| {
+            let mut cur = node.child_imut(1);
+            while let Some(ref n) = *cur {
+                self.stack.push(&**n);
+                cur = n.child_imut(0);
+            }
+            (&node.val().0, &node.val().1)
+        })
+    }
+}
+
+#[cfg(test)]
+mod avl_tree_map_test {
+    use bbstree::avl_tree_map::{ AVLTreeMap, AVLMapNode };
+    use algebra::laws::Rng;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn explicit_node_test() {
+        let mut map = AVLTreeMap::<usize, char, AVLMapNode<usize, char>>::none();
+        map.insert(3, 'c');
+        map.insert(1, 'a');
+        map.insert(2, 'b');
+        assert_eq!(map.remove(&2), Some('b'));
+        assert!(map.iter().eq(vec![(&1, &'a'), (&3, &'c')]));
+    }
+
+    #[test]
+    fn random_test() {
+        let mut map = AVLTreeMap::new();
+        let mut model = BTreeMap::new();
+        let mut rng = Rng::new(1);
+        for step in 0..3000 {
+            let key = rng.gen_index(200);
+            match rng.gen_index(8) {
+                0..=2 => assert_eq!(map.insert(key, step), model.insert(key, step)),
+                3 | 4 => assert_eq!(map.remove(&key), model.remove(&key)),
+                5 => {
+                    assert_eq!(map.get(&key), model.get(&key));
+                    assert_eq!(map.lower_bound(&key), model.range(key..).next());
+                    assert_eq!(map.upper_bound(&key), model.range(key + 1..).next());
+                    assert_eq!(map.rank(&key), model.range(..key).count());
+                }
+                6 => {
+                    let i = rng.gen_index(model.len() + 1);
+                    assert_eq!(map.nth(i), model.iter().nth(i));
+                }
+                _ => {
+                    let hi = key + rng.gen_index(50);
+                    assert!(map.range(key..hi).eq(model.range(key..hi)));
+                    assert!(map.range(key..=hi).eq(model.range(key..=hi)));
+                    assert!(map.range(..hi).eq(model.range(..hi)));
+                    assert!(map.range(key..).eq(model.range(key..)));
+                }
+            }
+            assert_eq!(map.len(), model.len());
+        }
+        assert!(map.iter().eq(model.iter()));
+        if let Some(v) = map.get_mut(&model.keys().next().cloned().unwrap()) { *v = 0; }
+        assert_eq!(map.iter().next().map(|(_, v)| *v), Some(0));
+    }
+}
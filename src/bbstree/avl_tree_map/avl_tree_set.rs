@@ -0,0 +1,81 @@
+use bbstree::avl_tree_map::avl_tree_map::{ self, AVLTreeMap };
+use std::ops::RangeBounds;
+
+pub struct AVLTreeSet<K: Ord> {
+    map: AVLTreeMap<K, ()>,
+}
+
+impl<K: Ord> AVLTreeSet<K> {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self { map: AVLTreeMap::new() }
+    }
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+    pub fn insert(&mut self, key: K) -> bool {
+        self.map.insert(key, ()).is_none()
+    }
+    pub fn remove(&mut self, key: &K) -> bool {
+        self.map.remove(key).is_some()
+    }
+    pub fn contains(&self, key: &K) -> bool {
+        self.map.contains_key(key)
+    }
+    pub fn lower_bound(&self, key: &K) -> Option<&K> {
+        self.map.lower_bound(key).map(|(k, _)| k)
+    }
+    pub fn upper_bound(&self, key: &K) -> Option<&K> {
+        self.map.upper_bound(key).map(|(k, _)| k)
+    }
+    pub fn rank(&self, key: &K) -> usize {
+        self.map.rank(key)
+    }
+    pub fn nth(&self, i: usize) -> Option<&K> {
+        self.map.nth(i).map(|(k, _)| k)
+    }
+    pub fn iter(&self) -> Iter<'_, K> {
+        Iter { iter: self.map.iter() }
+    }
+    pub fn range<R: RangeBounds<K>>(&self, range: R) -> Iter<'_, K> {
+        Iter { iter: self.map.range(range) }
+    }
+}
+
+pub struct Iter<'a, K: Ord + 'a> {
+    iter: avl_tree_map::Iter<'a, K, ()>,
+}
+
+impl<'a, K: Ord> Iterator for Iter<'a, K> {
+    type Item = &'a K;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(k, _)| k)
+    }
+}
+
+#[cfg(test)]
+mod avl_tree_set_test {
+    use bbstree::avl_tree_map::AVLTreeSet;
+
+    #[test]
+    fn order_statistics_test() {
+        let mut set = AVLTreeSet::new();
+        for x in [5, 1, 9, 3, 7, 3].iter() { set.insert(*x); }
+        assert_eq!(set.len(), 5);
+        assert_eq!(set.nth(0), Some(&1));
+        assert_eq!(set.nth(2), Some(&5));
+        assert_eq!(set.nth(5), None);
+        assert_eq!(set.rank(&6), 3);
+        assert_eq!(set.lower_bound(&7), Some(&7));
+        assert_eq!(set.upper_bound(&7), Some(&9));
+        assert_eq!(set.upper_bound(&9), None);
+        assert!(set.remove(&5));
+        assert!(!set.remove(&5));
+        assert!(!set.contains(&5));
+        assert!(set.range(2..8).cloned().eq(vec![3, 7]));
+        assert!(set.iter().cloned().eq(vec![1, 3, 7, 9]));
+    }
+}
@@ -0,0 +1,8 @@
+pub mod avl_map_node;
+#[allow(clippy::module_inception)]
+pub mod avl_tree_map;
+pub mod avl_tree_set;
+
+pub use self::avl_map_node::AVLMapNode;
+pub use self::avl_tree_map::AVLTreeMap;
+pub use self::avl_tree_set::AVLTreeSet;
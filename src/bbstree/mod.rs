@@ -1,4 +1,9 @@
 pub mod node_traits;
 
 pub mod avl_tree_array;
+pub mod avl_tree_map;
+pub mod splay_tree_array;
+pub mod splay_tree_array_safe;
 
+#[cfg(test)]
+mod splay_tree_array_test;
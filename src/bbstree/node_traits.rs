@@ -1,4 +1,4 @@
-use algebra::Monoid;
+use algebra::{ Monoid, Effector };
 
 pub type Link<N> = Option<Box<N>>;
 
@@ -11,6 +11,12 @@ pub trait Node: Sized {
     fn set(&mut self, dir_node: Link<Self>, dir: usize);
     fn val(&self) -> &Self::Value;
     fn val_mut(&mut self) -> &mut Self::Value;
+    fn push(&mut self) {}
+}
+
+pub trait BuildNode: Node {
+    fn new(val: Self::Value) -> Self;
+    fn into_val(self) -> Self::Value;
 }
 
 pub trait SizeNode: Node {
@@ -18,7 +24,8 @@ pub trait SizeNode: Node {
 }
 
 pub trait MapNode: Node {
-    type Key;
+    type Key: Ord;
+    fn key(&self) -> &Self::Key;
 }
 
 pub trait HeightNode: Node {
@@ -30,24 +37,34 @@ pub trait FoldNode where Self: Node, <Self as Node>::Value: Monoid {
     fn fold(&self) -> &<Self as Node>::Value;
 }
 
+pub trait ReversibleNode: FoldNode where <Self as Node>::Value: Monoid {
+    fn reverse(&mut self);
+    fn rev_fold(&self) -> &<Self as Node>::Value;
+}
+
+pub trait EffectNode: FoldNode where <Self as Node>::Value: Monoid {
+    type Effector: Effector<Target=<Self as Node>::Value>;
+    fn effect(&mut self, e: &Self::Effector);
+}
+
 pub fn size<N: SizeNode>(link: &Link<N>) -> usize {
     match link {
-        &Some(ref node) => node.size(),
-        &None => 0,
+        Some(node) => node.size(),
+        None => 0,
     }
 }
 
 pub fn height<N: HeightNode>(link: &Link<N>) -> isize {
     match link {
-        &Some(ref node) => node.height(),
-        &None => 0,
+        Some(node) => node.height(),
+        None => 0,
     }
 }
 
 pub fn diff<N: HeightNode>(link: &Link<N>) -> isize {
     match link {
-        &Some(ref node) => node.diff(),
-        &None => 0
+        Some(node) => node.diff(),
+        None => 0
     }
 }
 
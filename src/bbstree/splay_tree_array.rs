@@ -1,12 +1,8 @@
 use std::cmp::Ordering::{ Equal, Greater, Less };
 use std::mem;
-use std::ptr::{ NonNull, self };
-use std::alloc::{ Layout, alloc };
-
-pub trait Monoid {
-    fn ope(&self, r: &Self) -> Self;
-    fn ide() -> Self;
-}
+use std::ops::{ Deref, DerefMut };
+use std::ptr::NonNull;
+use algebra::Monoid;
 
 pub struct SplayTree<T: Monoid> {
     root: Link<T>
@@ -24,7 +20,7 @@ struct Node<T: Monoid> {
 
 impl<T: Monoid> Node<T> {
     fn new(elem: T) -> Node<T> {
-        let mut node = Node { elem: elem, size: 1, left: None, right: None, fold: T::ide() };
+        let mut node = Node { elem, size: 1, left: None, right: None, fold: T::identity() };
         fix(&mut node);
         node
     }
@@ -32,16 +28,16 @@ impl<T: Monoid> Node<T> {
 
 fn size<T: Monoid>(link: & Link<T>) -> usize {
     match link {
-        &Some(ref node_ptr) => unsafe { node_ptr.as_ref().size },
-        &None => 0
+        Some(node_ptr) => unsafe { node_ptr.as_ref().size },
+        None => 0
     }
 }
 
 fn fix<T: Monoid>(node: &mut Node<T>) {
     let sz = size(&node.left) + size(&node.right) + 1;
     node.size = sz;
-    node.fold = match node.left { Some(ref left) => unsafe { left.as_ref().fold.ope(&node.elem) }, None => T::ide().ope(&node.elem) };
-    node.fold = match node.right { Some(ref right) => unsafe { node.fold.ope(&right.as_ref().fold) }, None => node.fold.ope(&T::ide()) };
+    node.fold = match node.left { Some(ref left) => unsafe { left.as_ref().fold.op(&node.elem) }, None => node.elem.clone() };
+    node.fold = match node.right { Some(ref right) => unsafe { node.fold.op(&right.as_ref().fold) }, None => node.fold.clone() };
 }
 
 fn cut_left<T: Monoid>(node: &mut Node<T>) -> Link<T> {
@@ -57,35 +53,29 @@ fn cut_right<T: Monoid>(node: &mut Node<T>) -> Link<T> {
 }
 
 fn set_left<T: Monoid>(node: &mut Node<T>, left: Link<T>) {
-    //assert!(node.left.is_none(), "cant set");
     node.left = left;
     fix(node);
 }
 
 fn set_right<T: Monoid>(node: &mut Node<T>, right: Link<T>) {
-    //assert!(node.right.is_none(), "cant set");
     node.right = right;
     fix(node);
 }
 
 fn fix_sub_left<T: Monoid>(n: &mut Link<T>) {
-    if n.is_none() { return }
-    let t = n;
-    unsafe {
-        fix_sub_left(&mut t.unwrap().as_mut().right);
-        {
-            fix(t.unwrap().as_mut());
+    if let Some(mut t) = *n {
+        unsafe {
+            fix_sub_left(&mut t.as_mut().right);
+            fix(t.as_mut());
         }
     }
 }
 
 fn fix_sub_right<T: Monoid>(n: &mut Link<T>) {
-    if n.is_none() { return }
-    let t = n;
-    unsafe {
-        fix_sub_right(&mut t.unwrap().as_mut().left);
-        {
-            fix(t.unwrap().as_mut());
+    if let Some(mut t) = *n {
+        unsafe {
+            fix_sub_right(&mut t.as_mut().left);
+            fix(t.as_mut());
         }
     }
 }
@@ -108,7 +98,7 @@ fn splay<T: Monoid>(node: &mut NonNull<Node<T>>, n: usize) {
                         };
                         if size(&left.as_ref().left).cmp(&sz) == Greater {
                             set_left(node.as_mut(), cut_right(left.as_mut()));
-                            mem::swap(node.as_mut(), left.as_mut());
+                            mem::swap(node, &mut left);
                             let next = cut_left(node.as_mut());
                             set_right(node.as_mut(), Some(left));
                             match next {
@@ -129,7 +119,7 @@ fn splay<T: Monoid>(node: &mut NonNull<Node<T>>, n: usize) {
                         if size(&right.as_ref().left).cmp(&sz) == Less {
                             sz = sz - size(&right.as_ref().left) - 1;
                             set_right(node.as_mut(), cut_left(right.as_mut()));
-                            mem::swap(node.as_mut(), right.as_mut());
+                            mem::swap(node, &mut right);
                             let next = cut_right(node.as_mut());
                             set_left(node.as_mut(), Some(right));
                             match next {
@@ -143,8 +133,8 @@ fn splay<T: Monoid>(node: &mut NonNull<Node<T>>, n: usize) {
                     }
                 }
             }
-            mem::swap(&mut cut_left(node.as_mut()), le);
-            mem::swap(&mut cut_right(node.as_mut()), ri);
+            *le = cut_left(node.as_mut());
+            *ri = cut_right(node.as_mut());
         }
         fix_sub_left(&mut sub_left);
         fix_sub_right(&mut sub_right);
@@ -155,7 +145,6 @@ fn splay<T: Monoid>(node: &mut NonNull<Node<T>>, n: usize) {
 
 
 fn split<T: Monoid>(node: Link<T>, i: usize) -> (Link<T>, Link<T>) {
-    //assert!(i <= size(&node), "over");
     match node {
         None => (None, None),
         Some(mut r) => unsafe {
@@ -185,36 +174,52 @@ fn merge<T: Monoid>(left: Link<T>, right: Link<T>) -> Link<T> {
     }
 }
 
+fn free<T: Monoid>(link: Link<T>) {
+    let mut stack: Vec<_> = link.into_iter().collect();
+    while let Some(node) = stack.pop() {
+        let node = unsafe { Box::from_raw(node.as_ptr()) };
+        stack.extend(node.left);
+        stack.extend(node.right);
+    }
+}
+
 
 impl<T: Monoid> SplayTree<T>{
+    #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
         SplayTree { root: None, }
     }
 
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        size(&self.root)
+    }
+
     pub fn at(&mut self, i: usize) -> &T {
-        //assert!(!self.root.is_none(), "what at");
+        assert!(i < self.len(), "at(): out of range");
         splay(self.root.as_mut().unwrap(), i);
         unsafe { &self.root.as_ref().unwrap().as_ref().elem }
     }
 
-    pub fn at_mut(&mut self, i: usize) -> &mut T {
-        //assert!(!self.root.is_none(), "what at");
-        unsafe {
-            splay(self.root.as_mut().unwrap(), i);
-            &mut self.root.as_mut().unwrap().as_mut().elem
-        }
+    pub fn at_mut(&mut self, i: usize) -> AtMut<'_, T> {
+        assert!(i < self.len(), "at_mut(): out of range");
+        splay(self.root.as_mut().unwrap(), i);
+        AtMut { node: unsafe { self.root.as_mut().unwrap().as_mut() } }
     }
 
     pub fn set(&mut self, i: usize, elem: T) {
+        assert!(i < self.len(), "set(): out of range");
         unsafe {
-            splay(self.root.as_mut().unwrap(), i);
-            self.root.unwrap().as_mut().elem = self.root.unwrap().as_ref().elem.ope(&elem);
-            fix(self.root.unwrap().as_mut());
+            let root = self.root.as_mut().unwrap();
+            splay(root, i);
+            root.as_mut().elem = root.as_ref().elem.op(&elem);
+            fix(root.as_mut());
         }
     }
 
-    pub fn split(self, i: usize) -> (Self, Self) {
-        let (l, r) = split(self.root, i);
+    pub fn split(mut self, i: usize) -> (Self, Self) {
+        assert!(i <= self.len(), "split(): out of range");
+        let (l, r) = split(self.root.take(), i);
         (SplayTree { root: l }, SplayTree { root: r })
     }
 
@@ -222,18 +227,17 @@ impl<T: Monoid> SplayTree<T>{
         self.root = merge(self.root.take(), right.root.take());
     }
 
-    pub fn fold(&self) -> &T {
-        unsafe { &self.root.as_ref().unwrap().as_ref().fold } 
+    pub fn fold(&self) -> T {
+        match self.root {
+            Some(ref node) => unsafe { node.as_ref().fold.clone() },
+            None => T::identity(),
+        }
     }
 
     pub fn insert(&mut self, i: usize, elem: T) {
+        assert!(i <= self.len(), "insert(): out of range");
         unsafe {
-            let align = mem::align_of::<Node<T>>();
-            let elem_size = mem::size_of::<Node<T>>();
-            let mut node_ptr = alloc(Layout::from_size_align_unchecked(elem_size, align));
-            ptr::write(node_ptr as *mut _, Node::new(elem));
-            let mut node = NonNull::new_unchecked(node_ptr as *mut _);
-            fix(node.as_mut());
+            let mut node = NonNull::new_unchecked(Box::into_raw(Box::new(Node::new(elem))));
             let (left, right) = split(self.root.take(), i);
             set_left(node.as_mut(), left);
             set_right(node.as_mut(), right);
@@ -242,8 +246,34 @@ impl<T: Monoid> SplayTree<T>{
     }
 
     pub fn erase(&mut self, i: usize) {
+        assert!(i < self.len(), "erase(): out of range");
         let (ll, right) = split(self.root.take(), i + 1);
-        let (left, _) = split(ll, i);
+        let (left, mid) = split(ll, i);
+        free(mid);
         self.root = merge(left, right);
     }
 }
+
+// the element at the root, refolded once the borrow ends
+pub struct AtMut<'a, T: Monoid> {
+    node: &'a mut Node<T>,
+}
+
+impl<'a, T: Monoid> Deref for AtMut<'a, T> {
+    type Target = T;
+    fn deref(&self) -> &T { &self.node.elem }
+}
+
+impl<'a, T: Monoid> DerefMut for AtMut<'a, T> {
+    fn deref_mut(&mut self) -> &mut T { &mut self.node.elem }
+}
+
+impl<'a, T: Monoid> Drop for AtMut<'a, T> {
+    fn drop(&mut self) { fix(self.node); }
+}
+
+impl<T: Monoid> Drop for SplayTree<T> {
+    fn drop(&mut self) {
+        free(self.root.take());
+    }
+}
@@ -1,111 +1,40 @@
 use std::cmp::Ordering::{Equal, Greater, Less};
 use std::mem;
+use std::ops::{ Deref, DerefMut };
+use bbstree::node_traits::*;
+use algebra::{ Magma, Monoid, Unital };
 
-pub trait Monoid {
-    fn ope(&self, r: &Self) -> Self;
-    fn ide() -> Self;
+pub struct SplayTree<N: SizeNode> {
+    root: Link<N>
 }
 
-pub struct SplayTree<T: Monoid> {
-    root: Link<T>
-}
-
-type Link<T> = Option<Box<Node<T>>>;
-
-struct Node<T: Monoid> {
-    elem: T,
-    size: usize,
-    left: Link<T>,
-    right: Link<T>,
-    fold: T
-}
-
-impl<T: Monoid> Node<T> {
-    fn new(elem: T) -> Node<T> {
-        let mut node = Node { elem: elem, size: 1, left: None, right: None, fold: T::ide() };
-        fix(&mut node);
-        node
-    }
-}
-
-fn size<T: Monoid>(link: & Link<T>) -> usize {
-    match link {
-        &Some(ref boxed_node) => boxed_node.size,
-        &None => 0
-    }
-}
-
-fn fix<T: Monoid>(node: &mut Node<T>) {
-    let sz = size(&node.left) + size(&node.right) + 1;
-    node.size = sz;
-    node.fold = match node.left { Some(ref left) => left.as_ref().fold.ope(&node.elem), None => T::ide().ope(&node.elem) };
-    node.fold = match node.right { Some(ref right) => node.fold.ope(&right.as_ref().fold), None => node.fold.ope(&T::ide()) };
-}
-
-fn cut_left<T: Monoid>(node: &mut Node<T>) -> Link<T> {
-    let nn = node.left.take();
-    fix(node);
-    nn
-}
-
-fn cut_right<T: Monoid>(node: &mut Node<T>) -> Link<T> {
-    let nn = node.right.take();
-    fix(node);
-    nn
-}
-
-fn set_left<T: Monoid>(node: &mut Node<T>, left: Link<T>) {
-    //assert!(node.left.is_none(), "cant set");
-    node.left = left;
-    fix(node);
-}
-
-fn set_right<T: Monoid>(node: &mut Node<T>, right: Link<T>) {
-    //assert!(node.right.is_none(), "cant set");
-    node.right = right;
-    fix(node);
-}
-
-fn fix_sub_left<T: Monoid>(n: &mut Link<T>) {
-    if n.is_none() { return }
-    let t = n;
-    fix_sub_left(&mut t.as_mut().unwrap().right);
-    {
-        let no = t.as_mut().unwrap();
-        fix(no);
-    }
-}
-
-fn fix_sub_right<T: Monoid>(n: &mut Link<T>) {
-    if n.is_none() { return }
-    let t = n;
-    fix_sub_right(&mut t.as_mut().unwrap().left);
-    {
-        let no = t.as_mut().unwrap();
-        fix(no);
+fn fix_sub<N: SizeNode>(n: &mut Link<N>, dir: usize) {
+    if let Some(ref mut no) = *n {
+        fix_sub(no.child(dir), dir);
+        no.fix();
     }
 }
 
-fn splay<T: Monoid>(node: &mut Box<Node<T>>, n: usize) {
+fn splay<N: SizeNode>(node: &mut Box<N>, n: usize) {
     let mut sz = n;
-    let mut sub_left: Link<T> = None;
-    let mut sub_right: Link<T> = None;
+    let mut sub_left: Link<N> = None;
+    let mut sub_right: Link<N> = None;
     {
         let mut le = &mut sub_left;
         let mut ri = &mut sub_right;
         loop {
-            match size(&node.left).cmp(&sz) {
+            match size(node.child_imut(0)).cmp(&sz) {
                 Equal => { break }
                 Greater => {
-                    let mut left = match cut_left(node) {
+                    let mut left = match node.cut(0) {
                         Some(ll) => ll,
                         None => break
                     };
-                    if size(&left.left).cmp(&sz) == Greater {
-                        set_left(node, cut_right(&mut left));
+                    if size(left.child_imut(0)).cmp(&sz) == Greater {
+                        node.set(left.cut(1), 0);
                         mem::swap(node, &mut left);
-                        let next = cut_left(node);
-                        set_right(node, Some(left));
+                        let next = node.cut(0);
+                        node.set(Some(left), 1);
                         match next {
                             Some(l) => { left = l; },
                             None => { break }
@@ -113,20 +42,20 @@ fn splay<T: Monoid>(node: &mut Box<Node<T>>, n: usize) {
                     }
                     *ri = Some(mem::replace(node, left));
                     let t = ri;
-                    ri = &mut t.as_mut().unwrap().left;
+                    ri = t.as_mut().unwrap().child(0);
                 }
                 Less => {
-                    sz = sz - size(&node.left) - 1;
-                    let mut right = match cut_right(node) {
+                    sz = sz - size(node.child_imut(0)) - 1;
+                    let mut right = match node.cut(1) {
                         Some(rr) => rr,
                         None => break
                     };
-                    if size(&right.left).cmp(&sz) == Less {
-                        sz = sz - size(&right.left) - 1;
-                        set_right(node, cut_left(&mut right));
+                    if size(right.child_imut(0)).cmp(&sz) == Less {
+                        sz = sz - size(right.child_imut(0)) - 1;
+                        node.set(right.cut(0), 1);
                         mem::swap(node, &mut right);
-                        let next = cut_right(node);
-                        set_left(node, Some(right));
+                        let next = node.cut(1);
+                        node.set(Some(right), 0);
                         match next {
                             Some(r) => { right = r; },
                             None => { break }
@@ -134,42 +63,40 @@ fn splay<T: Monoid>(node: &mut Box<Node<T>>, n: usize) {
                     }
                     *le = Some(mem::replace(node, right));
                     let t = le;
-                    le = &mut t.as_mut().unwrap().right;
+                    le = t.as_mut().unwrap().child(1);
                 }
             }
         }
-        mem::swap(&mut cut_left(node), le);
-        mem::swap(&mut cut_right(node), ri);
+        *le = node.cut(0);
+        *ri = node.cut(1);
     }
-    fix_sub_left(&mut sub_left);
-    fix_sub_right(&mut sub_right);
-    set_left(node, sub_left);
-    set_right(node, sub_right);
-    
+    fix_sub(&mut sub_left, 1);
+    fix_sub(&mut sub_right, 0);
+    node.set(sub_left, 0);
+    node.set(sub_right, 1);
 }
 
-fn split<T: Monoid>(node: Link<T>, i: usize) -> (Link<T>, Link<T>) {
-    //assert!(i <= size(&node), "over");
+fn split<N: SizeNode>(node: Link<N>, i: usize) -> (Link<N>, Link<N>) {
     match node {
         None => (None, None),
         Some(mut r) => {
             if i == 0 { (None, Some(r)) }
-            else if i == r.size { (Some(r), None) }
+            else if i == r.size() { (Some(r), None) }
             else {
                 splay(&mut r , i - 1);
-                let right = cut_right(&mut r);
+                let right = r.cut(1);
                 (Some(r), right)
             }
         }
     }
 }
 
-fn merge<T: Monoid>(left: Link<T>, right: Link<T>) -> Link<T> {
+fn merge<N: SizeNode>(left: Link<N>, right: Link<N>) -> Link<N> {
     match (left, right) {
         (Some(mut l), r) => {
-            let sz = l.size;
+            let sz = l.size();
             splay(&mut l, sz - 1);
-            set_right(&mut l, r);
+            l.set(r, 1);
             Some(l)
         }
         (None, r) => {
@@ -178,31 +105,32 @@ fn merge<T: Monoid>(left: Link<T>, right: Link<T>) -> Link<T> {
     }
 }
 
-impl<T: Monoid> SplayTree<T>{
+impl<N: SizeNode> SplayTree<N> {
+    #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
         SplayTree { root: None, }
     }
 
-    pub fn at(&mut self, i: usize) -> &T {
-        //assert!(!self.root.is_none(), "what at");
-        splay(self.root.as_mut().unwrap(), i);
-        &self.root.as_ref().unwrap().elem
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        size(&self.root)
     }
 
-    pub fn at_mut(&mut self, i: usize) -> &mut T {
-        //assert!(!self.root.is_none(), "what at");
+    pub fn at(&mut self, i: usize) -> &N::Value {
+        assert!(i < self.len(), "at(): out of range");
         splay(self.root.as_mut().unwrap(), i);
-        &mut self.root.as_mut().unwrap().elem
+        self.root.as_ref().unwrap().val()
     }
 
-    pub fn set(&mut self, i: usize, elem: T) {
+    pub fn at_mut(&mut self, i: usize) -> AtMut<'_, N> {
+        assert!(i < self.len(), "at_mut(): out of range");
         splay(self.root.as_mut().unwrap(), i);
-        self.root.as_mut().unwrap().elem = self.root.as_ref().unwrap().elem.ope(&elem);
-        fix(self.root.as_mut().unwrap());
+        AtMut { node: self.root.as_mut().unwrap() }
     }
 
-    pub fn split(self, i: usize) -> (Self, Self) {
-        let (l, r) = split(self.root, i);
+    pub fn split(mut self, i: usize) -> (Self, Self) {
+        assert!(i <= self.len(), "split(): out of range");
+        let (l, r) = split(self.root.take(), i);
         (SplayTree { root: l }, SplayTree { root: r })
     }
 
@@ -210,26 +138,67 @@ impl<T: Monoid> SplayTree<T>{
         self.root = merge(self.root.take(), right.root.take());
     }
 
-    pub fn fold(&self) -> &T {
-        &self.root.as_ref().unwrap().fold
+    pub fn erase(&mut self, i: usize) {
+        assert!(i < self.len(), "erase(): out of range");
+        let (ll, right) = split(self.root.take(), i + 1);
+        let (left, _) = split(ll, i);
+        self.root = merge(left, right);
     }
+}
 
-    pub fn insert(&mut self, i: usize, elem: T) {
-        let mut node = Box::new(Node::new(elem));
-        fix(&mut node);
+impl<N: SizeNode + BuildNode> SplayTree<N> {
+    pub fn insert(&mut self, i: usize, val: N::Value) {
+        assert!(i <= self.len(), "insert(): out of range");
+        let mut node = Box::new(N::new(val));
         let (left, right) = split(self.root.take(), i);
-        set_left(&mut node, left);
-        set_right(&mut node, right);
+        node.set(left, 0);
+        node.set(right, 1);
         self.root = Some(node);
     }
+}
 
-    pub fn erase(&mut self, i: usize) {
-        let (ll, right) = split(self.root.take(), i + 1);
-        let (left, _) = split(ll, i);
-        self.root = merge(left, right);
+impl<N: SizeNode + FoldNode> SplayTree<N> where N::Value: Monoid {
+    pub fn set(&mut self, i: usize, val: N::Value) {
+        assert!(i < self.len(), "set(): out of range");
+        let root = self.root.as_mut().unwrap();
+        splay(root, i);
+        let x = root.val().op(&val);
+        *root.val_mut() = x;
+        root.fix();
     }
+
+    pub fn fold(&self) -> N::Value {
+        match self.root {
+            Some(ref node) => node.fold().clone(),
+            None => <N::Value as Unital>::identity(),
+        }
+    }
+}
+
+// the value at the root, refolded once the borrow ends
+pub struct AtMut<'a, N: SizeNode> {
+    node: &'a mut N,
+}
+
+impl<'a, N: SizeNode> Deref for AtMut<'a, N> {
+    type Target = N::Value;
+    fn deref(&self) -> &N::Value { self.node.val() }
 }
 
-#[cfg(test)]
-mod test {
+impl<'a, N: SizeNode> DerefMut for AtMut<'a, N> {
+    fn deref_mut(&mut self) -> &mut N::Value { self.node.val_mut() }
+}
+
+impl<'a, N: SizeNode> Drop for AtMut<'a, N> {
+    fn drop(&mut self) { self.node.fix(); }
+}
+
+impl<N: SizeNode> Drop for SplayTree<N> {
+    fn drop(&mut self) {
+        let mut stack: Vec<_> = self.root.take().into_iter().collect();
+        while let Some(mut node) = stack.pop() {
+            stack.extend(node.child(0).take());
+            stack.extend(node.child(1).take());
+        }
+    }
 }
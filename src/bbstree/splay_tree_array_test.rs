@@ -0,0 +1,125 @@
+use algebra::*;
+use algebra::laws::Rng;
+
+monoid!(#[derive(Debug, PartialEq)] Am, usize, 0, |a, b| a + b; Commutative);
+
+// a non-commutative monoid: concatenation of the first and last element
+monoid!(#[derive(Debug, PartialEq)] Fl, Option<(usize, usize)>, None, |a, b| match (a, b) {
+    (Some((x, _)), Some((_, y))) => Some((x, y)),
+    (l, None) => l,
+    (None, r) => r,
+});
+
+fn model_fold<T: Monoid>(v: &[T]) -> T {
+    v.iter().fold(T::identity(), |acc, x| acc.op(x))
+}
+
+macro_rules! random_test {
+    ($name: ident, $tree: ty, $value: expr) => {
+        #[test]
+        fn $name() {
+            let mut rng = Rng::new(88172645463325252);
+            let mut tree = <$tree>::new();
+            let mut model = Vec::new();
+            for _ in 0..3000 {
+                match rng.gen_index(6) {
+                    0 | 1 => {
+                        let i = rng.gen_index(model.len() + 1);
+                        let x = rng.gen_index(1000);
+                        tree.insert(i, $value(x));
+                        model.insert(i, $value(x));
+                    }
+                    2 if !model.is_empty() => {
+                        let i = rng.gen_index(model.len());
+                        tree.erase(i);
+                        model.remove(i);
+                    }
+                    3 if !model.is_empty() => {
+                        let i = rng.gen_index(model.len());
+                        let x = rng.gen_index(1000);
+                        tree.set(i, $value(x));
+                        model[i] = model[i].op(&$value(x));
+                    }
+                    4 => {
+                        let i = rng.gen_index(model.len() + 1);
+                        let (mut left, right) = tree.split(i);
+                        assert_eq!(left.fold(), model_fold(&model[..i]));
+                        assert_eq!(right.fold(), model_fold(&model[i..]));
+                        left.merge(right);
+                        tree = left;
+                    }
+                    _ => {
+                        assert_eq!(tree.len(), model.len());
+                        if !model.is_empty() {
+                            let i = rng.gen_index(model.len());
+                            assert_eq!(*tree.at(i), model[i]);
+                        }
+                        assert_eq!(tree.fold(), model_fold(&model));
+                    }
+                }
+            }
+            for i in 0..model.len() {
+                assert_eq!(*tree.at(i), model[i]);
+            }
+        }
+    }
+}
+
+mod unsafe_tree {
+    use super::*;
+    use bbstree::splay_tree_array::SplayTree;
+
+    random_test!(random_sum_test, SplayTree<Am>, Am);
+    random_test!(random_first_last_test, SplayTree<Fl>, |x| Fl(Some((x, x))));
+
+    #[test]
+    fn set_combines_test() {
+        let mut tree = SplayTree::new();
+        for i in 0..4 { tree.insert(i, Am(i + 1)); }
+        tree.set(2, Am(10));
+        assert_eq!(*tree.at(2), Am(13));
+        assert_eq!(tree.fold(), Am(20));
+    }
+
+    #[test]
+    fn at_mut_test() {
+        let mut tree = SplayTree::new();
+        for i in 0..5 { tree.insert(i, Fl(Some((i, i)))); }
+        *tree.at_mut(0) = Fl(Some((7, 7)));
+        tree.at_mut(4).0 = Some((9, 9));
+        assert_eq!(tree.fold(), Fl(Some((7, 9))));
+        let (left, right) = tree.split(1);
+        assert_eq!(left.fold(), Fl(Some((7, 7))));
+        assert_eq!(right.fold(), Fl(Some((1, 9))));
+    }
+}
+
+mod safe_tree {
+    use super::*;
+    use bbstree::splay_tree_array_safe::SplayTree;
+    use bbstree::avl_tree_array::ArrFoldNode;
+
+    random_test!(random_sum_test, SplayTree<ArrFoldNode<Am>>, Am);
+    random_test!(random_first_last_test, SplayTree<ArrFoldNode<Fl>>, |x| Fl(Some((x, x))));
+
+    #[test]
+    fn set_combines_test() {
+        let mut tree = SplayTree::<ArrFoldNode<Am>>::new();
+        for i in 0..4 { tree.insert(i, Am(i + 1)); }
+        tree.set(2, Am(10));
+        assert_eq!(*tree.at(2), Am(13));
+        assert_eq!(tree.fold(), Am(20));
+    }
+
+    #[test]
+    fn at_mut_test() {
+        let mut tree = SplayTree::<ArrFoldNode<Fl>>::new();
+        for i in 0..5 { tree.insert(i, Fl(Some((i, i)))); }
+        *tree.at_mut(0) = Fl(Some((7, 7)));
+        tree.at_mut(4).0 = Some((9, 9));
+        assert_eq!(tree.fold(), Fl(Some((7, 9))));
+        let (left, right) = tree.split(1);
+        assert_eq!(left.fold(), Fl(Some((7, 7))));
+        assert_eq!(right.fold(), Fl(Some((1, 9))));
+    }
+}
@@ -31,9 +31,11 @@ impl<T> Clone for Stream<T> {
 }
 
 impl<T> Stream<T> {
+    #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
         Stream { st: Rc::new(RefCell::new(StreamRaw::Nil)) }
     }
+    #[allow(clippy::len_without_is_empty)]
     pub fn len(&self) -> usize {
         let st = &*self.st.borrow();
         match *st {
@@ -61,7 +63,7 @@ impl<T> Stream<T> {
             let mut now = stream.clone();
             let mut r = StreamRaw::Nil;
             let mut rlen = 0;
-            while let &StreamRaw::Cons(ref x, ref s, _) = {
+            while let StreamRaw::Cons(x, s, _) = {
                 now.eval();
                 &*now.clone().st.borrow()
             } {
@@ -120,13 +122,14 @@ impl<T> Clone for Queue<T> {
 }
 
 impl<T> Queue<T> {
+    #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
         Queue { front: Stream::new(), rear: Stream::new() }
     }
 
     fn check(front: Stream<T>, rear: Stream<T>) -> Self {
         if rear.len() <= front.len() {
-            Queue { front: front, rear: rear }
+            Queue { front, rear }
         }
         else {
             Queue { front: front.concat(rear.reverse()), rear: Stream::new() }
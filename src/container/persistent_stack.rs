@@ -12,11 +12,12 @@ pub struct PersistentStack<T> {
 }
 
 impl<T> PersistentStack<T> {
+    #[allow(clippy::new_without_default)]
     pub fn new() -> Self { Self { head: None } }
     pub fn push(&self, elem: T) -> Self {
         Self {
             head: Some(Rc::new( Node {
-                elem: elem, 
+                elem, 
                 next: self.head.clone(),
             } ))
         }
@@ -30,7 +31,7 @@ impl<T> PersistentStack<T> {
         self.head.as_ref().map(|node| &node.elem)
     }
     pub fn iter(&self) -> Iter<'_, T> {
-        Iter { next: self.head.as_ref().map(|node| &**node) }
+        Iter { next: self.head.as_deref() }
     }
 }
 
@@ -54,7 +55,7 @@ impl<'a, T> Iterator for Iter<'a, T> {
     type Item = &'a T;
     fn next(&mut self) -> Option<Self::Item> {
         self.next.map(| node | {
-            self.next = node.next.as_ref().map(|node| &**node);
+            self.next = node.next.as_deref();
             &node.elem
         })
     }
@@ -11,6 +11,7 @@ pub struct Vector<T> {
 }
 
 impl<T> Vector<T> {
+    #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
         assert!(mem::size_of::<T>() != 0, "We're not ready to handle ZSTs");
         Vector { ptr: NonNull::dangling(), len: 0, cap: 0 }
@@ -28,7 +29,7 @@ impl<T> Vector<T> {
                 let new_cap = self.cap * 2;
                 let old_num_bytes = self.cap * elem_size;
 
-                assert!(old_num_bytes <= (::std::isize::MAX as usize) / 2, "capacity overflow");
+                assert!(old_num_bytes <= (isize::MAX as usize) / 2, "capacity overflow");
 
                 let new_num_bytes = old_num_bytes * 2;
                 let ptr = realloc(self.ptr.as_ptr() as *mut _, 
@@ -37,7 +38,7 @@ impl<T> Vector<T> {
                 (new_cap, ptr)
             };
 
-            if ptr.is_null() { assert!(false, "ptr is null"); }
+            assert!(!ptr.is_null(), "ptr is null");
             self.ptr = NonNull::new_unchecked(ptr as *mut _);
             self.cap = new_cap;
         }
@@ -47,7 +48,7 @@ impl<T> Vector<T> {
         if self.len == self.cap { self.grow() }
 
         unsafe {
-            ptr::write(self.ptr.as_ptr().offset(self.len as isize), elem);
+            ptr::write(self.ptr.as_ptr().add(self.len), elem);
         }
 
         self.len += 1;
@@ -59,7 +60,7 @@ impl<T> Vector<T> {
         } else {
             self.len -= 1;
             unsafe {
-                Some(ptr::read(self.ptr.as_ptr().offset(self.len as isize)))
+                Some(ptr::read(self.ptr.as_ptr().add(self.len)))
             }
         }
     }
@@ -68,7 +69,7 @@ impl<T> Vector<T> {
 impl<T> Drop for Vector<T> {
     fn drop(&mut self) {
         if self.cap != 0 {
-            while let Some(_) = self.pop() {}
+            while self.pop().is_some() {}
 
             let align = mem::align_of::<T>();
             let elem_size = mem::size_of::<T>();
@@ -102,5 +103,5 @@ fn vector_test() {
     assert!(v.pop() == Some(2));
     assert!(v.pop() == Some(1));
     assert!(v.pop() == Some(0));
-    assert!(v.pop() == None);
+    assert!(v.pop().is_none());
 }
@@ -0,0 +1,141 @@
+use algebra::*;
+use segment_tree::bounds;
+use std::ops::RangeBounds;
+
+// only prefix folds are available for a plain monoid; arbitrary ranges need a group
+pub struct FenwickTree<T: Monoid> {
+    node: Vec<T>,
+    n: usize,
+}
+
+impl<T: Monoid> FenwickTree<T> {
+    pub fn new(n: usize) -> Self {
+        FenwickTree { node: vec![T::identity(); n + 1], n }
+    }
+
+    pub fn len(&self) -> usize { self.n }
+
+    pub fn is_empty(&self) -> bool { self.n == 0 }
+
+    pub fn prefix(&self, r: usize) -> T {
+        assert!(r <= self.n, "prefix(): out of range");
+        let mut i = r;
+        let mut acc = T::identity();
+        while i > 0 {
+            acc = self.node[i].op(&acc);
+            i -= i & i.wrapping_neg();
+        }
+        acc
+    }
+
+    pub fn lower_bound<F: Fn(&T) -> bool>(&self, pred: F) -> usize {
+        let mut pos = 0;
+        let mut acc = T::identity();
+        let mut step = 1;
+        while step * 2 <= self.n { step *= 2; }
+        while step > 0 {
+            if pos + step <= self.n {
+                let nx = acc.op(&self.node[pos + step]);
+                if pred(&nx) {
+                    pos += step;
+                    acc = nx;
+                }
+            }
+            step >>= 1;
+        }
+        pos
+    }
+}
+
+// a node folds its values out of order, so building and point updates require commutativity
+impl<T: Monoid + Commutative> FenwickTree<T> {
+    pub fn from_vec(vec: Vec<T>) -> Self {
+        let n = vec.len();
+        let mut node = Vec::with_capacity(n + 1);
+        node.push(T::identity());
+        node.extend(vec);
+        for i in 1..n + 1 {
+            let j = i + (i & i.wrapping_neg());
+            if j <= n { node[j] = node[i].op(&node[j]); }
+        }
+        FenwickTree { node, n }
+    }
+
+    pub fn add(&mut self, i: usize, x: T) {
+        assert!(i < self.n, "add(): out of range");
+        let mut i = i + 1;
+        while i <= self.n {
+            self.node[i] = self.node[i].op(&x);
+            i += i & i.wrapping_neg();
+        }
+    }
+}
+
+// range folds are differences of prefix folds
+impl<T: Group + Commutative> FenwickTree<T> {
+    pub fn fold<R: RangeBounds<usize>>(&self, range: R) -> T {
+        let (l, r) = bounds(&range, self.n);
+        self.prefix(l).inverse().op(&self.prefix(r))
+    }
+
+    pub fn get(&self, i: usize) -> T {
+        self.fold(i..i + 1)
+    }
+
+    pub fn set(&mut self, i: usize, x: T) {
+        let d = self.get(i).inverse().op(&x);
+        self.add(i, d);
+    }
+}
+
+#[cfg(test)]
+mod fenwick_tree_test {
+    use algebra::*;
+    use fenwick_tree::fenwick_tree::FenwickTree;
+    use std::cmp::max;
+
+    monoid!(#[derive(Debug)] Am, i64, 0, |a, b| a + b; Commutative);
+
+    impl Invertible for Am {
+        fn inverse(&self) -> Self { Am(-self.0) }
+    }
+
+    monoid!(#[derive(Debug)] Mx, i64, i64::MIN, |a, b| max(a, b); Commutative);
+
+    #[test]
+    fn rsq_test() {
+        let mut v: Vec<i64> = (0..13).map(|i| i * 7 % 5).collect();
+        let mut fw = FenwickTree::from_vec(v.iter().map(|&x| Am(x)).collect());
+        fw.add(3, Am(10));
+        v[3] += 10;
+        fw.set(8, Am(-4));
+        v[8] = -4;
+        for l in 0..14 {
+            assert_eq!(fw.prefix(l).0, v[..l].iter().sum::<i64>());
+            for r in l..14 {
+                assert_eq!(fw.fold(l..r).0, v[l..r].iter().sum::<i64>());
+            }
+        }
+        assert_eq!(fw.get(8).0, -4);
+    }
+
+    #[test]
+    fn prefix_max_test() {
+        let v = [3, 1, 4, 1, 5, 9, 2, 6, 5];
+        let mut fw = FenwickTree::new(v.len());
+        for (i, &x) in v.iter().enumerate() { fw.add(i, Mx(x)); }
+        for r in 1..v.len() + 1 {
+            assert_eq!(fw.prefix(r).0, *v[..r].iter().max().unwrap());
+        }
+    }
+
+    #[test]
+    fn lower_bound_test() {
+        let v = [3, 0, 4, 1, 5, 9, 2];
+        let fw = FenwickTree::from_vec(v.iter().map(|&x| Am(x)).collect());
+        for w in 1..30 {
+            let expected = (0..v.len() + 1).take_while(|&r| v[..r].iter().sum::<i64>() < w).last().unwrap();
+            assert_eq!(fw.lower_bound(|s| s.0 < w), expected);
+        }
+    }
+}
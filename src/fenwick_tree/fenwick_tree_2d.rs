@@ -0,0 +1,97 @@
+use algebra::*;
+use segment_tree::bounds;
+use std::ops::RangeBounds;
+
+// only prefix folds are available for a plain monoid; arbitrary ranges need a group
+pub struct FenwickTree2D<T: Monoid> {
+    node: Vec<Vec<T>>,
+    h: usize,
+    w: usize,
+}
+
+impl<T: Monoid> FenwickTree2D<T> {
+    pub fn new(h: usize, w: usize) -> Self {
+        FenwickTree2D { node: vec![vec![T::identity(); w + 1]; h + 1], h, w }
+    }
+
+    pub fn height(&self) -> usize { self.h }
+
+    pub fn width(&self) -> usize { self.w }
+
+    pub fn prefix(&self, r: usize, c: usize) -> T {
+        assert!(r <= self.h && c <= self.w, "prefix(): out of range");
+        let mut acc = T::identity();
+        let mut a = r;
+        while a > 0 {
+            let mut b = c;
+            while b > 0 {
+                acc = self.node[a][b].op(&acc);
+                b -= b & b.wrapping_neg();
+            }
+            a -= a & a.wrapping_neg();
+        }
+        acc
+    }
+}
+
+// a node folds its values out of order, so building and point updates require commutativity
+impl<T: Monoid + Commutative> FenwickTree2D<T> {
+    pub fn add(&mut self, i: usize, j: usize, x: T) {
+        assert!(i < self.h && j < self.w, "add(): out of range");
+        let mut a = i + 1;
+        while a <= self.h {
+            let mut b = j + 1;
+            while b <= self.w {
+                self.node[a][b] = self.node[a][b].op(&x);
+                b += b & b.wrapping_neg();
+            }
+            a += a & a.wrapping_neg();
+        }
+    }
+}
+
+// range folds are differences of prefix folds
+impl<T: Group + Commutative> FenwickTree2D<T> {
+    pub fn fold<R: RangeBounds<usize>, C: RangeBounds<usize>>(&self, rows: R, cols: C) -> T {
+        let (r1, r2) = bounds(&rows, self.h);
+        let (c1, c2) = bounds(&cols, self.w);
+        self.prefix(r2, c2)
+            .op(&self.prefix(r1, c2).inverse())
+            .op(&self.prefix(r2, c1).inverse())
+            .op(&self.prefix(r1, c1))
+    }
+}
+
+#[cfg(test)]
+mod fenwick_tree_2d_test {
+    use algebra::*;
+    use fenwick_tree::fenwick_tree_2d::FenwickTree2D;
+
+    monoid!(#[derive(Debug)] Am, i64, 0, |a, b| a + b; Commutative);
+
+    impl Invertible for Am {
+        fn inverse(&self) -> Self { Am(-self.0) }
+    }
+
+    #[test]
+    fn rect_sum_test() {
+        let (h, w) = (5, 7);
+        let mut grid = vec![vec![0i64; w]; h];
+        let mut fw = FenwickTree2D::new(h, w);
+        for k in 0..20 {
+            let (i, j, x) = (k * 3 % h, k * 5 % w, k as i64 - 7);
+            fw.add(i, j, Am(x));
+            grid[i][j] += x;
+        }
+        for r1 in 0..h + 1 {
+            for r2 in r1..h + 1 {
+                for c1 in 0..w + 1 {
+                    for c2 in c1..w + 1 {
+                        let expected: i64 = grid[r1..r2].iter().map(|row| row[c1..c2].iter().sum::<i64>()).sum();
+                        assert_eq!(fw.fold(r1..r2, c1..c2).0, expected);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,3 @@
+#[allow(clippy::module_inception)]
+pub mod fenwick_tree;
+pub mod fenwick_tree_2d;
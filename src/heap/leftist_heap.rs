@@ -17,14 +17,14 @@ impl<T: Ord> Heap<T> {
         }
     }
     fn meld(l: Rc<Self>, r: &LeftistHeap<T>) -> Self {
-        let right = LeftistHeap::meld(&l.as_ref().right, &r);
+        let right = LeftistHeap::meld(&l.as_ref().right, r);
         let left = l.as_ref().left.clone();
         if left.rank() >= right.rank() {
             Heap {
                 rank: right.rank() + 1,
                 elem: l.as_ref().elem.clone(),
-                left: left,
-                right: right,
+                left,
+                right,
             }
         }
         else {
@@ -36,7 +36,7 @@ impl<T: Ord> Heap<T> {
             }
         }
     }
-    fn peek(&self) -> &T { &self.elem.as_ref() }
+    fn peek(&self) -> &T { self.elem.as_ref() }
     fn pop(&self) -> LeftistHeap<T> {
         LeftistHeap::meld(&self.left, &self.right)
     }
@@ -50,13 +50,14 @@ pub enum LeftistHeap<T: Ord> {
 impl<T: Ord> Clone for LeftistHeap<T> {
     fn clone(&self) -> Self {
         match self {
-            &LeftistHeap::None => LeftistHeap::None,
-            &LeftistHeap::Some(ref heap) => LeftistHeap::Some(heap.clone()),
+            LeftistHeap::None => LeftistHeap::None,
+            LeftistHeap::Some(heap) => LeftistHeap::Some(heap.clone()),
         }
     }
 }
 
 impl<T: Ord> LeftistHeap<T> {
+    #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
         LeftistHeap::None
     }
@@ -65,16 +66,16 @@ impl<T: Ord> LeftistHeap<T> {
     }
     fn rank(&self) -> usize {
         match self {
-            &LeftistHeap::None => 0,
-            &LeftistHeap::Some(ref heap) => heap.as_ref().rank,
+            LeftistHeap::None => 0,
+            LeftistHeap::Some(heap) => heap.as_ref().rank,
         }
     }
     pub fn meld(a: &Self, b: &Self) -> Self {
         match a {
-            &LeftistHeap::None => b.clone(),
-            &LeftistHeap::Some(ref ah) => match b {
-                &LeftistHeap::None => a.clone(),
-                &LeftistHeap::Some(ref bh) => 
+            LeftistHeap::None => b.clone(),
+            LeftistHeap::Some(ah) => match b {
+                LeftistHeap::None => a.clone(),
+                LeftistHeap::Some(bh) => 
                     if ah.as_ref().peek() >= bh.as_ref().peek() {
                         LeftistHeap::Some(Rc::new(Heap::meld(ah.clone(), b)))
                     }
@@ -86,8 +87,8 @@ impl<T: Ord> LeftistHeap<T> {
     }
     pub fn peek(&self) -> Option<&T> {
         match self {
-            &LeftistHeap::None => None,
-            &LeftistHeap::Some(ref heap) => Some(heap.as_ref().peek()),
+            LeftistHeap::None => None,
+            LeftistHeap::Some(heap) => Some(heap.as_ref().peek()),
         }
     }
     pub fn insert(&self, x: T) -> Self {
@@ -95,8 +96,8 @@ impl<T: Ord> LeftistHeap<T> {
     }
     pub fn pop(&self) -> Self {
         match self {
-            &LeftistHeap::None => unreachable!("can't pop an empty heap"),
-            &LeftistHeap::Some(ref heap) => heap.as_ref().pop(),
+            LeftistHeap::None => unreachable!("can't pop an empty heap"),
+            LeftistHeap::Some(heap) => heap.as_ref().pop(),
         }
     }
 }
@@ -71,7 +71,7 @@ impl<T: Monoid, E: Effector<Target=T>> Node for EffNode<T, E> {
             if let Some(left) = self.ch[0] {
                 self.sz += left.as_ref().sz;
                 self.fold = left.as_ref().fold().op(&self.fold);
-                self.rev_fold = self.fold.op(&left.as_ref().rev_fold);
+                self.rev_fold = self.rev_fold.op(&left.as_ref().rev_fold);
             }
             if let Some(right) = self.ch[1] {
                 self.sz += right.as_ref().sz;
@@ -90,6 +90,7 @@ impl<T: Monoid, E: Effector<Target=T>> EffectNode for EffNode<T, E> {
     fn effect(&mut self, e: E) {
         self.val = e.effect(&self.val, 1);
         self.fold = e.effect(&self.fold, self.size());
+        self.rev_fold = e.effect(&self.rev_fold, self.size());
         self.eff = self.eff.op(&e);
     }
     fn fold(&self) -> &T { &self.fold }
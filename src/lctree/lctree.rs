@@ -1,7 +1,10 @@
+#![allow(deprecated)]
+
 use std::ptr::NonNull;
 use lctree::node_traits::*;
 use algebra::*;
 
+#[deprecated(note = "LctNode copies alias one node and never free it; use LinkCutForest instead")]
 #[derive(Clone, Copy)]
 pub struct LctNode<N: Node> {
     node: NonNull<N>
@@ -46,6 +49,7 @@ impl<N: EffectNode> LctNode<N> where N::Value: Monoid {
 }
 
 #[cfg(test)]
+#[allow(deprecated)]
 mod lct_test {
     use lctree::lctree::LctNode;
     use lctree::valnode::ValNode;
@@ -71,7 +75,7 @@ mod lct_test {
             (4, 3, 0),
             (5, 2, 0),
         ];
-        let nodes: Vec<_> = (0..n).map(|i| LctNode::<ValNode<usize>>::new(i)).collect();
+        let nodes: Vec<_> = (0..n).map(LctNode::<ValNode<usize>>::new).collect();
         for i in 0..n {
             for v in links[i].iter() {
                 nodes[*v].link(&nodes[i]);
@@ -82,35 +86,9 @@ mod lct_test {
         }
     }
 
-    #[derive(Clone, Debug)]
-    struct Sm(usize);
-
-    impl Magma for Sm {
-        fn op(&self, right: &Self) -> Self { Sm(self.0 + right.0) }
-    }
-    impl Associative for Sm {}
-    impl Unital for Sm {
-        fn identity() -> Self { Sm(0) }
-    }
-
-    #[derive(Clone, Debug)]
-    struct Aq(usize);
+    monoid!(#[derive(Debug)] Sm, usize, 0, |a, b| a + b);
 
-    impl Magma for Aq {
-        fn op(&self, right: &Self) -> Self {
-            Aq(self.0 + right.0)
-        }
-    }
-    impl Associative for Aq {}
-    impl Unital for Aq {
-        fn identity() -> Self { Aq(0) }
-    }
-    impl Effector for Aq {
-        type Target = Sm;
-        fn effect(&self, t: &Self::Target, s: usize) -> Self::Target {
-            Sm(t.0 + self.0 * s)
-        }
-    }
+    effector!(#[derive(Debug)] Aq, usize, 0, |a, b| a + b, Sm, |e, t, s| Sm(t.0 + e * s));
     #[derive(Clone, Copy)]
     enum Query {
         Update(usize, usize),
@@ -0,0 +1,581 @@
+use std::fmt;
+use std::error::Error;
+use std::ptr::NonNull;
+use lctree::node_traits::*;
+use algebra::*;
+
+// `gen` counts how many times the slot has been freed, so ids of removed nodes never alias new ones
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct NodeId {
+    index: usize,
+    gen: usize,
+}
+
+impl NodeId {
+    pub fn index(&self) -> usize { self.index }
+}
+
+// the slot index lives right after the node, so a node pointer leads back to its id
+#[repr(C)]
+struct Slot<N> {
+    node: N,
+    index: usize,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LinkCutError {
+    AlreadyConnected,
+    NotRoot,
+    NoParent,
+}
+
+impl fmt::Display for LinkCutError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            LinkCutError::AlreadyConnected => write!(f, "the nodes are already connected"),
+            LinkCutError::NotRoot => write!(f, "the child is not the root of its tree"),
+            LinkCutError::NoParent => write!(f, "the node has no parent"),
+        }
+    }
+}
+
+impl Error for LinkCutError {}
+
+pub struct PathVertices {
+    iter: ::std::vec::IntoIter<NodeId>,
+}
+
+impl Iterator for PathVertices {
+    type Item = NodeId;
+    fn next(&mut self) -> Option<NodeId> { self.iter.next() }
+    fn size_hint(&self) -> (usize, Option<usize>) { self.iter.size_hint() }
+}
+
+impl DoubleEndedIterator for PathVertices {
+    fn next_back(&mut self) -> Option<NodeId> { self.iter.next_back() }
+}
+
+impl ExactSizeIterator for PathVertices {}
+
+pub struct LinkCutForest<N: Node> {
+    nodes: Vec<Link<N>>,
+    gens: Vec<usize>,
+    free: Vec<usize>,
+    len: usize,
+}
+
+impl<N: Node> LinkCutForest<N> {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        LinkCutForest { nodes: Vec::new(), gens: Vec::new(), free: Vec::new(), len: 0 }
+    }
+    fn node(&self, v: NodeId) -> NonNull<N> {
+        match self.nodes.get(v.index) {
+            Some(&Some(node)) if self.gens[v.index] == v.gen => node,
+            _ => panic!("node {} does not exist", v.index),
+        }
+    }
+    fn id(&self, node: NonNull<N>) -> NodeId {
+        let index = unsafe { (*(node.as_ptr() as *const Slot<N>)).index };
+        NodeId { index, gen: self.gens[index] }
+    }
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    pub fn contains(&self, v: NodeId) -> bool {
+        matches!(self.nodes.get(v.index), Some(&Some(_))) && self.gens[v.index] == v.gen
+    }
+    pub fn add_node(&mut self, val: N::Value) -> NodeId {
+        let index = match self.free.pop() {
+            Some(i) => i,
+            None => {
+                self.nodes.push(None);
+                self.gens.push(0);
+                self.nodes.len() - 1
+            }
+        };
+        let slot = Box::new(Slot { node: N::new(val), index });
+        self.nodes[index] = Some(unsafe { NonNull::new_unchecked(Box::into_raw(slot) as *mut N) });
+        self.len += 1;
+        NodeId { index, gen: self.gens[index] }
+    }
+    /// Takes O(n) time: the children of `v` off its preferred path only point to `v`,
+    /// so every node of the forest is scanned to detach them.
+    pub fn remove_node(&mut self, v: NodeId) {
+        let t = self.node(v);
+        expose(t);
+        if unsafe { t.as_ref().child(0).is_some() } { lct_cut(t); }
+        for node in self.nodes.iter().flatten() {
+            let mut node = *node;
+            unsafe {
+                if *node.as_ref().parent() == Some(t) { *node.as_mut().parent_mut() = None; }
+            }
+        }
+        self.nodes[v.index] = None;
+        self.gens[v.index] += 1;
+        self.free.push(v.index);
+        self.len -= 1;
+        unsafe { drop(Box::from_raw(t.as_ptr() as *mut Slot<N>)); }
+    }
+    pub fn find_root(&mut self, v: NodeId) -> NodeId {
+        let r = lct_root(self.node(v));
+        self.id(r)
+    }
+    pub fn parent(&mut self, v: NodeId) -> Option<NodeId> {
+        lct_parent(self.node(v)).map(|p| self.id(p))
+    }
+    pub fn connected(&mut self, u: NodeId, v: NodeId) -> bool {
+        self.find_root(u) == self.find_root(v)
+    }
+    pub fn try_link(&mut self, child: NodeId, parent: NodeId) -> Result<(), LinkCutError> {
+        if self.find_root(child) != child { return Err(LinkCutError::NotRoot); }
+        if self.connected(child, parent) { return Err(LinkCutError::AlreadyConnected); }
+        lct_link(self.node(parent), self.node(child));
+        Ok(())
+    }
+    pub fn try_cut(&mut self, child: NodeId) -> Result<(), LinkCutError> {
+        if self.parent(child).is_none() { return Err(LinkCutError::NoParent); }
+        lct_cut(self.node(child));
+        Ok(())
+    }
+    fn with_path<R, F: FnOnce(NonNull<N>) -> R>(&mut self, u: NodeId, v: NodeId, f: F) -> R {
+        let (tu, tv) = (self.node(u), self.node(v));
+        let r = lct_root(tu);
+        assert!(lct_root(tv) == r, "the nodes {} and {} are not connected", u.index, v.index);
+        lct_evert(tu);
+        expose(tv);
+        let res = f(tv);
+        lct_evert(r);
+        res
+    }
+    pub fn path_len(&mut self, u: NodeId, v: NodeId) -> usize {
+        self.with_path(u, v, |t| unsafe { t.as_ref().size() - 1 })
+    }
+    pub fn depth(&mut self, v: NodeId) -> usize {
+        let t = self.node(v);
+        expose(t);
+        unsafe { t.as_ref().size() - 1 }
+    }
+    pub fn kth_ancestor(&mut self, v: NodeId, k: usize) -> Option<NodeId> {
+        let t = self.node(v);
+        expose(t);
+        let d = unsafe { t.as_ref().size() - 1 };
+        if k > d { return None; }
+        lct_kth(t, d - k).map(|a| self.id(a))
+    }
+    pub fn jump(&mut self, u: NodeId, v: NodeId, k: usize) -> Option<NodeId> {
+        self.with_path(u, v, |t| lct_kth(t, k)).map(|w| self.id(w))
+    }
+    pub fn path_vertices(&mut self, u: NodeId, v: NodeId) -> PathVertices {
+        let path = self.with_path(u, v, lct_path);
+        PathVertices { iter: path.into_iter().map(|w| self.id(w)).collect::<Vec<_>>().into_iter() }
+    }
+    pub fn link(&mut self, child: NodeId, parent: NodeId) {
+        if let Err(e) = self.try_link(child, parent) { panic!("link(): {}", e); }
+    }
+    pub fn cut(&mut self, child: NodeId) {
+        if let Err(e) = self.try_cut(child) { panic!("cut(): {}", e); }
+    }
+    pub fn evert(&mut self, v: NodeId) {
+        lct_evert(self.node(v));
+    }
+    pub fn lca(&mut self, u: NodeId, v: NodeId) -> Option<NodeId> {
+        if !self.connected(u, v) { return None; }
+        lct_lca(self.node(u), self.node(v)).map(|lca| self.id(lca))
+    }
+    pub fn value(&mut self, v: NodeId) -> &N::Value {
+        let t = self.node(v);
+        expose(t);
+        unsafe { (*t.as_ptr()).value() }
+    }
+    pub fn set_value(&mut self, v: NodeId, val: N::Value) {
+        let mut t = self.node(v);
+        expose(t);
+        unsafe {
+            *t.as_mut().value_mut() = val;
+            t.as_mut().fix();
+        }
+    }
+}
+
+impl<N: EffectNode> LinkCutForest<N> where N::Value: Monoid {
+    pub fn fold(&mut self, v: NodeId) -> N::Value {
+        let t = self.node(v);
+        expose(t);
+        unsafe { t.as_ref().fold().clone() }
+    }
+    pub fn effect(&mut self, v: NodeId, e: N::Effector) {
+        lct_effect(self.node(v), e);
+    }
+    pub fn path_fold(&mut self, u: NodeId, v: NodeId) -> N::Value {
+        self.with_path(u, v, |t| unsafe { t.as_ref().fold().clone() })
+    }
+    pub fn path_effect(&mut self, u: NodeId, v: NodeId, e: N::Effector) {
+        self.with_path(u, v, |mut t| unsafe {
+            t.as_mut().effect(e);
+            t.as_mut().push();
+        })
+    }
+}
+
+impl<N: VirtualNode> LinkCutForest<N> {
+    pub fn subtree_fold(&mut self, v: NodeId, root: NodeId) -> N::Value {
+        self.with_path(root, v, |t| unsafe { t.as_ref().subtree_fold() })
+    }
+    pub fn subtree_size(&mut self, v: NodeId, root: NodeId) -> usize {
+        self.with_path(root, v, |t| unsafe { t.as_ref().subtree_size() })
+    }
+}
+
+impl<N: Node> Drop for LinkCutForest<N> {
+    fn drop(&mut self) {
+        for node in self.nodes.drain(..).flatten() {
+            unsafe { drop(Box::from_raw(node.as_ptr() as *mut Slot<N>)); }
+        }
+    }
+}
+
+#[cfg(test)]
+mod link_cut_forest_test {
+    use lctree::link_cut_forest::*;
+    use lctree::valnode::ValNode;
+    use lctree::effectnode::EffNode;
+    use lctree::subtreenode::SubtreeNode;
+    use algebra::monoids::{ Affine, Sum };
+    use algebra::laws::Rng;
+
+    monoid!(#[derive(Debug)] Sm, usize, 0, |a, b| a + b);
+    effector!(#[derive(Debug)] Aq, usize, 0, |a, b| a + b, Sm, |e, t, s| Sm(t.0 + e * s));
+
+    const SEED: u64 = 88172645463325252;
+
+    // the forest as plain parent pointers, which the tests compare against
+    struct Naive {
+        par: Vec<Option<usize>>,
+    }
+
+    impl Naive {
+        fn new(n: usize) -> Self {
+            Naive { par: vec![None; n] }
+        }
+        // `v` and its ancestors, from `v` up to the root
+        fn ancestors(&self, mut v: usize) -> Vec<usize> {
+            let mut res = vec![v];
+            while let Some(p) = self.par[v] {
+                res.push(p);
+                v = p;
+            }
+            res
+        }
+        fn lca(&self, u: usize, v: usize) -> Option<usize> {
+            let pv = self.ancestors(v);
+            self.ancestors(u).into_iter().find(|w| pv.contains(w))
+        }
+        fn root(&self, v: usize) -> usize {
+            *self.ancestors(v).last().unwrap()
+        }
+        fn path(&self, u: usize, v: usize) -> Vec<usize> {
+            let (mut pu, mut pv) = (self.ancestors(u), self.ancestors(v));
+            let mut lca = None;
+            while !pu.is_empty() && pu.last() == pv.last() {
+                lca = pu.pop();
+                pv.pop();
+            }
+            pu.push(lca.unwrap());
+            pu.extend(pv.into_iter().rev());
+            pu
+        }
+        fn evert(&mut self, v: usize) {
+            let (mut prev, mut cur) = (None, Some(v));
+            while let Some(c) = cur {
+                cur = self.par[c];
+                self.par[c] = prev;
+                prev = Some(c);
+            }
+        }
+        fn subtree(&self, v: usize) -> Vec<usize> {
+            (0..self.par.len()).filter(|&w| self.ancestors(w).contains(&v)).collect()
+        }
+        fn remove(&mut self, v: usize) {
+            for p in self.par.iter_mut() {
+                if *p == Some(v) { *p = None; }
+            }
+            self.par[v] = None;
+        }
+    }
+
+    #[test]
+    fn lca_test() {
+        let links = [vec![1, 2, 3], vec![4, 5], vec![], vec![], vec![], vec![6, 7], vec![], vec![]];
+        let mut lct = LinkCutForest::<ValNode<usize>>::new();
+        let nodes: Vec<NodeId> = (0..8).map(|i| lct.add_node(i)).collect();
+        for i in 0..8 {
+            for &v in links[i].iter() { lct.link(nodes[v], nodes[i]); }
+        }
+        for &(u, v, ans) in [(4, 6, 1), (4, 7, 1), (4, 3, 0), (5, 2, 0), (6, 7, 5)].iter() {
+            let lca = lct.lca(nodes[u], nodes[v]).unwrap();
+            assert_eq!(lca, nodes[ans]);
+            assert_eq!(*lct.value(lca), ans);
+        }
+        lct.evert(nodes[5]);
+        assert_eq!(lct.lca(nodes[4], nodes[3]), Some(nodes[1]));
+        let c = lct.add_node(8);
+        assert_eq!(lct.lca(nodes[4], c), None);
+        assert_eq!(lct.lca(c, c), Some(c));
+    }
+
+    #[test]
+    fn stale_id_test() {
+        let mut lct = LinkCutForest::<ValNode<usize>>::new();
+        let a = lct.add_node(0);
+        let b = lct.add_node(1);
+        lct.link(b, a);
+        lct.remove_node(b);
+        let c = lct.add_node(2);
+        assert_eq!(c.index(), b.index());
+        assert!(!lct.contains(b) && lct.contains(c));
+        assert_eq!(lct.find_root(c), c);
+        assert_eq!(lct.len(), 2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn stale_id_panic_test() {
+        let mut lct = LinkCutForest::<ValNode<usize>>::new();
+        let a = lct.add_node(0);
+        lct.remove_node(a);
+        lct.add_node(1);
+        lct.value(a);
+    }
+
+    #[test]
+    fn root_path_query_test() {
+        let links = [vec![1, 2], vec![3, 5], vec![], vec![], vec![], vec![4]];
+        let mut vals = LinkCutForest::<EffNode<Sm, Aq>>::new();
+        let mut effs = LinkCutForest::<EffNode<Sm, Aq>>::new();
+        let vn: Vec<NodeId> = (0..6).map(|_| vals.add_node(Sm(0))).collect();
+        let en: Vec<NodeId> = (0..6).map(|_| effs.add_node(Sm(0))).collect();
+        for i in 0..6 {
+            for &v in links[i].iter() {
+                vals.link(vn[v], vn[i]);
+                effs.link(en[v], en[i]);
+            }
+        }
+        let query = [(None, 1, 0, 0), (Some(10), 3, 0, 0), (None, 2, 0, 0), (Some(20), 4, 0, 0),
+                     (None, 3, 10, 40), (Some(40), 5, 0, 0), (None, 4, 60, 150)];
+        for &(w, v, in_vals, in_effs) in query.iter() {
+            match w {
+                Some(w) => {
+                    let x = vals.value(vn[v]).op(&Sm(w));
+                    vals.set_value(vn[v], x);
+                    effs.effect(en[v], Aq(w));
+                }
+                None => {
+                    assert_eq!(vals.fold(vn[v]).0, in_vals);
+                    assert_eq!(effs.fold(en[v]).0 - effs.value(en[0]).0, in_effs);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn random_test() {
+        let n = 12;
+        let mut lct = LinkCutForest::<EffNode<Sm, Aq>>::new();
+        let mut nodes: Vec<Option<NodeId>> = (0..n).map(|i| Some(lct.add_node(Sm(i)))).collect();
+        let mut naive = Naive::new(n);
+        let mut val: Vec<usize> = (0..n).collect();
+        let mut rng = Rng::new(SEED);
+        for _ in 0..3000 {
+            let (u, v) = (rng.gen_index(n), rng.gen_index(n));
+            if nodes[u].is_none() {
+                nodes[u] = Some(lct.add_node(Sm(0)));
+                val[u] = 0;
+                continue;
+            }
+            let uid = nodes[u].unwrap();
+            match rng.gen_index(6) {
+                0 => if let Some(vid) = nodes[v] {
+                    let expected = if naive.par[u].is_some() { Err(LinkCutError::NotRoot) }
+                        else if naive.root(v) == u { Err(LinkCutError::AlreadyConnected) }
+                        else { Ok(()) };
+                    assert_eq!(lct.try_link(uid, vid), expected);
+                    if expected.is_ok() { naive.par[u] = Some(v); }
+                },
+                1 => {
+                    let expected = if naive.par[u].is_some() { Ok(()) } else { Err(LinkCutError::NoParent) };
+                    assert_eq!(lct.try_cut(uid), expected);
+                    naive.par[u] = None;
+                }
+                2 => {
+                    let w = rng.gen_index(10);
+                    lct.effect(uid, Aq(w));
+                    for p in naive.ancestors(u) { val[p] += w; }
+                }
+                3 => if rng.gen_index(10) == 0 {
+                    lct.remove_node(uid);
+                    nodes[u] = None;
+                    naive.remove(u);
+                },
+                _ => {
+                    let expected: usize = naive.ancestors(u).iter().map(|&p| val[p]).sum();
+                    assert_eq!(lct.fold(uid).0, expected);
+                    assert_eq!(lct.value(uid).0, val[u]);
+                    assert_eq!(lct.parent(uid), naive.par[u].map(|p| nodes[p].unwrap()));
+                    assert_eq!(lct.find_root(uid), nodes[naive.root(u)].unwrap());
+                    if let Some(vid) = nodes[v] {
+                        assert_eq!(lct.connected(uid, vid), naive.root(u) == naive.root(v));
+                    }
+                }
+            }
+        }
+        assert_eq!(lct.len(), nodes.iter().filter(|v| v.is_some()).count());
+    }
+
+    effector!(Noop, (), (), |_a, _b| (), Affine<i64>, |_e, t, _sz| *t);
+
+    #[test]
+    fn path_query_test() {
+        let n = 10;
+        let mut aff = LinkCutForest::<EffNode<Affine<i64>, Noop>>::new();
+        let mut sum = LinkCutForest::<EffNode<Sm, Aq>>::new();
+        let an: Vec<NodeId> = (0..n).map(|i| aff.add_node(Affine(i as i64 % 3 + 1, i as i64 % 4))).collect();
+        let sn: Vec<NodeId> = (0..n).map(|i| sum.add_node(Sm(i))).collect();
+        let va: Vec<Affine<i64>> = (0..n).map(|i| Affine(i as i64 % 3 + 1, i as i64 % 4)).collect();
+        let mut vs: Vec<usize> = (0..n).collect();
+        let mut naive = Naive::new(n);
+        let mut rng = Rng::new(SEED);
+        for _ in 0..3000 {
+            let (u, v) = (rng.gen_index(n), rng.gen_index(n));
+            match rng.gen_index(4) {
+                0 => if aff.try_link(an[u], an[v]).is_ok() {
+                    sum.link(sn[u], sn[v]);
+                    naive.par[u] = Some(v);
+                },
+                1 => if aff.try_cut(an[u]).is_ok() {
+                    sum.cut(sn[u]);
+                    naive.par[u] = None;
+                },
+                _ => if aff.connected(an[u], an[v]) {
+                    let p = naive.path(u, v);
+                    assert_eq!(aff.path_len(an[u], an[v]), p.len() - 1);
+                    assert_eq!(aff.path_fold(an[u], an[v]), p.iter().fold(Affine::identity(), |acc, &w| acc.op(&va[w])));
+                    let w = rng.gen_index(5);
+                    sum.path_effect(sn[u], sn[v], Aq(w));
+                    for &y in p.iter() { vs[y] += w; }
+                    let (a, b) = (p[rng.gen_index(p.len())], p[rng.gen_index(p.len())]);
+                    assert_eq!(sum.path_fold(sn[a], sn[b]).0, naive.path(a, b).iter().map(|&y| vs[y]).sum::<usize>());
+                    assert_eq!(aff.parent(an[u]), naive.par[u].map(|y| an[y]));
+                },
+            }
+        }
+    }
+
+    // assigns an affine map to every vertex of a path, so a fold over `sz` vertices becomes the map composed `sz` times
+    effector!(Asg, Option<Affine<i64>>, None, |a, b| b.or(a), Affine<i64>,
+        |e, t, sz| e.map_or(*t, |f| (0..sz).fold(Affine::identity(), |acc: Affine<i64>, _| acc.op(&f))));
+
+    #[test]
+    fn rev_fold_test() {
+        let n = 10;
+        let mut lct = LinkCutForest::<EffNode<Affine<i64>, Asg>>::new();
+        let mut val: Vec<Affine<i64>> = (0..n).map(|i| Affine(if i % 2 == 0 { 1 } else { -1 }, i as i64)).collect();
+        let nodes: Vec<NodeId> = val.iter().map(|&f| lct.add_node(f)).collect();
+        let mut naive = Naive::new(n);
+        let mut rng = Rng::new(SEED);
+        for _ in 0..3000 {
+            let (u, v) = (rng.gen_index(n), rng.gen_index(n));
+            match rng.gen_index(6) {
+                0 => if lct.try_link(nodes[u], nodes[v]).is_ok() { naive.par[u] = Some(v); },
+                1 => if lct.try_cut(nodes[u]).is_ok() { naive.par[u] = None; },
+                2 => {
+                    lct.evert(nodes[u]);
+                    naive.evert(u);
+                }
+                3 => if lct.connected(nodes[u], nodes[v]) {
+                    let f = Affine(if rng.gen_index(2) == 0 { 1 } else { -1 }, rng.gen_index(10) as i64);
+                    lct.path_effect(nodes[u], nodes[v], Asg(Some(f)));
+                    for w in naive.path(u, v) { val[w] = f; }
+                },
+                _ => if lct.connected(nodes[u], nodes[v]) {
+                    let expected = naive.path(u, v).iter().fold(Affine::identity(), |acc, &w| acc.op(&val[w]));
+                    assert_eq!(lct.path_fold(nodes[u], nodes[v]), expected);
+                    assert_eq!(*lct.value(nodes[u]), val[u]);
+                },
+            }
+        }
+    }
+
+    #[test]
+    fn subtree_test() {
+        let n = 10;
+        let mut lct = LinkCutForest::<SubtreeNode<Sum<i64>>>::new();
+        let nodes: Vec<NodeId> = (0..n).map(|i| lct.add_node(Sum(i as i64))).collect();
+        let mut val: Vec<i64> = (0..n as i64).collect();
+        let mut naive = Naive::new(n);
+        let mut rng = Rng::new(SEED);
+        for _ in 0..3000 {
+            let (u, v) = (rng.gen_index(n), rng.gen_index(n));
+            match rng.gen_index(6) {
+                0 => if lct.try_link(nodes[u], nodes[v]).is_ok() { naive.par[u] = Some(v); },
+                1 => if lct.try_cut(nodes[u]).is_ok() { naive.par[u] = None; },
+                2 => {
+                    lct.evert(nodes[u]);
+                    naive.evert(u);
+                }
+                3 => {
+                    let w = rng.gen_index(100) as i64 - 50;
+                    lct.set_value(nodes[u], Sum(w));
+                    val[u] = w;
+                }
+                _ => {
+                    if lct.connected(nodes[u], nodes[v]) {
+                        let mut rerooted = Naive { par: naive.par.clone() };
+                        rerooted.evert(v);
+                        assert_eq!(lct.subtree_size(nodes[u], nodes[v]), rerooted.subtree(u).len());
+                        let expected: i64 = rerooted.subtree(u).iter().map(|&w| val[w]).sum();
+                        assert_eq!(lct.subtree_fold(nodes[u], nodes[v]).0, expected);
+                        assert_eq!(lct.parent(nodes[u]), naive.par[u].map(|w| nodes[w]));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn level_ancestor_test() {
+        let n = 10;
+        let mut lct = LinkCutForest::<ValNode<usize>>::new();
+        let nodes: Vec<NodeId> = (0..n).map(|i| lct.add_node(i)).collect();
+        let mut naive = Naive::new(n);
+        let mut rng = Rng::new(SEED);
+        for _ in 0..3000 {
+            let (u, v) = (rng.gen_index(n), rng.gen_index(n));
+            match rng.gen_index(5) {
+                0 => if lct.try_link(nodes[u], nodes[v]).is_ok() { naive.par[u] = Some(v); },
+                1 => if lct.try_cut(nodes[u]).is_ok() { naive.par[u] = None; },
+                2 => {
+                    lct.evert(nodes[u]);
+                    naive.evert(u);
+                }
+                _ => {
+                    let ancestors = naive.ancestors(u);
+                    assert_eq!(lct.depth(nodes[u]), ancestors.len() - 1);
+                    let k = rng.gen_index(ancestors.len() + 1);
+                    assert_eq!(lct.kth_ancestor(nodes[u], k), ancestors.get(k).map(|&w| nodes[w]));
+                    assert_eq!(lct.lca(nodes[u], nodes[v]), naive.lca(u, v).map(|w| nodes[w]));
+                    if lct.connected(nodes[u], nodes[v]) {
+                        let p: Vec<NodeId> = naive.path(u, v).into_iter().map(|w| nodes[w]).collect();
+                        assert!(lct.path_vertices(nodes[u], nodes[v]).eq(p.iter().cloned()));
+                        let k = rng.gen_index(p.len() + 1);
+                        assert_eq!(lct.jump(nodes[u], nodes[v], k), p.get(k).cloned());
+                        assert_eq!(lct.parent(nodes[u]), naive.par[u].map(|w| nodes[w]));
+                    }
+                }
+            }
+        }
+    }
+}
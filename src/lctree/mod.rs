@@ -1,5 +1,8 @@
 pub mod node_traits;
 pub mod valnode;
 pub mod effectnode;
+pub mod subtreenode;
+#[allow(clippy::module_inception)]
 pub mod lctree;
+pub mod link_cut_forest;
 
@@ -16,6 +16,7 @@ pub trait Node: Sized {
     fn value(&self) -> &Self::Value;
     fn value_mut(&mut self) -> &mut Self::Value;
     fn size(&self) -> usize;
+    fn swap_light(&mut self, _light: &Link<Self>, _heavy: &Link<Self>) {}
 }
 
 pub trait EffectNode: Node where Self::Value: Monoid {
@@ -24,18 +25,23 @@ pub trait EffectNode: Node where Self::Value: Monoid {
     fn fold(&self) -> &Self::Value;
 }
 
+pub trait VirtualNode: Node {
+    fn subtree_fold(&self) -> Self::Value;
+    fn subtree_size(&self) -> usize;
+}
+
 fn is_root<N: Node>(node: &NonNull<N>) -> bool {
     unsafe {
-        match node.as_ref().parent().clone() {
+        match *node.as_ref().parent() {
             None => true,
             Some(p) => {
-                ( match p.as_ref().child(0) {
-                    &None => true,
-                    &Some(left) => left != *node,
+                ( match *p.as_ref().child(0) {
+                    None => true,
+                    Some(left) => left != *node,
                 } ) &&
-                ( match p.as_ref().child(1) {
-                    &None => true,
-                    &Some(right) => right != *node,
+                ( match *p.as_ref().child(1) {
+                    None => true,
+                    Some(right) => right != *node,
                 } )
             }
         }
@@ -57,8 +63,8 @@ fn parent_dir<N: Node>(parent: &Link<N>, child: &NonNull<N>) -> Option<usize> {
 
 fn rotate<N: Node>(mut t: NonNull<N>, dir: usize) {
     unsafe {
-        let mut x = t.as_ref().parent().unwrap().clone();
-        let y = x.as_ref().parent().clone();
+        let mut x = t.as_ref().parent().unwrap();
+        let y = *x.as_ref().parent();
 
         *x.as_mut().child_mut(dir ^ 1) = *t.as_ref().child(dir);
         if let Some(mut tr) = *t.as_ref().child(dir) {
@@ -82,14 +88,14 @@ fn splay<N: Node>(mut t: NonNull<N>) {
     unsafe {
         t.as_mut().push();
         while !is_root(&t) {
-            let mut q = t.as_ref().parent().clone().unwrap();
+            let mut q = t.as_ref().parent().unwrap();
             if is_root(&q) {
                 q.as_mut().push();
                 t.as_mut().push();
                 rotate(t, parent_dir(&Some(q), &t).unwrap() ^ 1);
             }
             else {
-                let mut r = q.as_ref().parent().clone().unwrap();
+                let mut r = q.as_ref().parent().unwrap();
                 r.as_mut().push();
                 q.as_mut().push();
                 t.as_mut().push();
@@ -114,10 +120,12 @@ pub fn expose<N: Node>(t: NonNull<N>) -> Link<N> {
     unsafe {
         while let Some(mut cc) = cur {
             splay(cc);
+            let light = *cc.as_ref().child(1);
+            cc.as_mut().swap_light(&light, &rp);
             *cc.as_mut().child_mut(1) = rp;
             cc.as_mut().fix();
             rp = cur;
-            cur = cc.as_ref().parent().clone();
+            cur = *cc.as_ref().parent();
         }
     }
     splay(t);
@@ -131,6 +139,7 @@ pub fn lct_link<N: Node>(mut parent: NonNull<N>, mut child: NonNull<N>) {
         *child.as_mut().parent_mut() = Some(parent);
         *parent.as_mut().child_mut(1) = Some(child);
         child.as_mut().fix();
+        parent.as_mut().fix();
     }
 }
 
@@ -144,6 +153,72 @@ pub fn lct_cut<N: Node>(mut child: NonNull<N>) {
     }
 }
 
+pub fn lct_root<N: Node>(t: NonNull<N>) -> NonNull<N> {
+    expose(t);
+    let mut r = t;
+    unsafe {
+        r.as_mut().push();
+        while let Some(mut left) = *r.as_ref().child(0) {
+            left.as_mut().push();
+            r = left;
+        }
+    }
+    splay(r);
+    r
+}
+
+pub fn lct_parent<N: Node>(t: NonNull<N>) -> Link<N> {
+    expose(t);
+    let mut p = unsafe { *t.as_ref().child(0) }?;
+    unsafe {
+        p.as_mut().push();
+        while let Some(mut right) = *p.as_ref().child(1) {
+            right.as_mut().push();
+            p = right;
+        }
+    }
+    splay(p);
+    Some(p)
+}
+
+pub fn lct_kth<N: Node>(t: NonNull<N>, mut k: usize) -> Link<N> {
+    let mut cur = t;
+    unsafe {
+        if k >= cur.as_ref().size() { return None; }
+        loop {
+            cur.as_mut().push();
+            let left = cur.as_ref().child(0).map_or(0, |l| l.as_ref().size());
+            if k < left { cur = cur.as_ref().child(0).unwrap(); }
+            else if k == left { break; }
+            else {
+                k -= left + 1;
+                cur = cur.as_ref().child(1).unwrap();
+            }
+        }
+    }
+    splay(cur);
+    Some(cur)
+}
+
+pub fn lct_path<N: Node>(t: NonNull<N>) -> Vec<NonNull<N>> {
+    let mut res = Vec::with_capacity(unsafe { t.as_ref().size() });
+    let mut stack = Vec::new();
+    let mut cur = Some(t);
+    unsafe {
+        while cur.is_some() || !stack.is_empty() {
+            while let Some(mut c) = cur {
+                c.as_mut().push();
+                stack.push(c);
+                cur = *c.as_ref().child(0);
+            }
+            let c = stack.pop().unwrap();
+            res.push(c);
+            cur = *c.as_ref().child(1);
+        }
+    }
+    res
+}
+
 pub fn lct_lca<N: Node>(u: NonNull<N>, v: NonNull<N>) -> Link<N> {
     expose(u);
     expose(v)
@@ -0,0 +1,90 @@
+use lctree::node_traits::*;
+use algebra::*;
+
+pub struct SubtreeNode<T: Group + Commutative> {
+    ch: [Link<Self>; 2],
+    par: Link<Self>,
+    val: T,
+    sum: T,
+    virt: T,
+    rev: bool,
+    sz: usize,
+    tsz: usize,
+    vsz: usize,
+}
+
+impl<T: Group + Commutative> Node for SubtreeNode<T> {
+    type Value = T;
+    fn new(val: T) -> Self {
+        SubtreeNode {
+            ch: [ None, None ],
+            par: None,
+            val: val.clone(),
+            sum: val,
+            virt: T::identity(),
+            rev: false,
+            sz: 1,
+            tsz: 1,
+            vsz: 0,
+        }
+    }
+    fn push(&mut self) {
+        if self.rev {
+            if let Some(mut left) = self.ch[0] {
+                unsafe { left.as_mut().reverse(); }
+            }
+            if let Some(mut right) = self.ch[1] {
+                unsafe { right.as_mut().reverse(); }
+            }
+            self.rev = false;
+        }
+    }
+    fn reverse(&mut self) {
+        self.ch.swap(0, 1);
+        self.rev ^= true;
+    }
+    fn child(&self, dir: usize) -> &Link<Self> {
+        &self.ch[dir]
+    }
+    fn child_mut(&mut self, dir: usize) -> &mut Link<Self> {
+        &mut self.ch[dir]
+    }
+    fn parent(&self) -> &Link<Self> {
+        &self.par
+    }
+    fn parent_mut(&mut self) -> &mut Link<Self> {
+        &mut self.par
+    }
+    fn fix(&mut self) {
+        self.sz = 1;
+        self.tsz = 1 + self.vsz;
+        self.sum = self.val.op(&self.virt);
+        unsafe {
+            for c in self.ch.iter().flatten() {
+                self.sz += c.as_ref().sz;
+                self.tsz += c.as_ref().tsz;
+                self.sum = self.sum.op(&c.as_ref().sum);
+            }
+        }
+    }
+    fn value(&self) -> &T { &self.val }
+    fn value_mut(&mut self) -> &mut T { &mut self.val }
+    fn size(&self) -> usize { self.sz }
+    fn swap_light(&mut self, light: &Link<Self>, heavy: &Link<Self>) {
+        unsafe {
+            if let Some(l) = *light {
+                self.virt = self.virt.op(&l.as_ref().sum);
+                self.vsz += l.as_ref().tsz;
+            }
+            if let Some(h) = *heavy {
+                self.virt = self.virt.op(&h.as_ref().sum.inverse());
+                self.vsz -= h.as_ref().tsz;
+            }
+        }
+    }
+}
+
+impl<T: Group + Commutative> VirtualNode for SubtreeNode<T> {
+    fn subtree_fold(&self) -> T { self.val.op(&self.virt) }
+    fn subtree_size(&self) -> usize { 1 + self.vsz }
+}
@@ -14,7 +14,7 @@ impl<T> Node for ValNode<T> {
         ValNode {
             ch: [ None, None ],
             par: None,
-            val: val,
+            val,
             rev: false,
             sz: 1
         }
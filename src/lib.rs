@@ -1,8 +1,11 @@
+#[macro_use]
 pub mod algebra;
 
 pub mod container;
 pub mod heap;
 pub mod segment_tree;
+pub mod fenwick_tree;
+pub mod static_fold;
 pub mod bbstree;
 pub mod lctree;
 
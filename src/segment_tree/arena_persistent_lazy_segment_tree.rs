@@ -0,0 +1,207 @@
+use algebra::*;
+use segment_tree::bounds;
+use segment_tree::versions::{ Versions, ArenaNode, NIL };
+use std::ops::RangeBounds;
+
+struct Node<T: Monoid, E: Effector<Target=T>> {
+    data: T,
+    eff: E,
+    left: usize,
+    right: usize,
+}
+
+impl<T: Monoid, E: Effector<Target=T>> ArenaNode for Node<T, E> {
+    fn children(&mut self) -> (&mut usize, &mut usize) { (&mut self.left, &mut self.right) }
+}
+
+pub struct ArenaPersistentLazySegmentTree<T: Monoid, E: Effector<Target=T>> {
+    nodes: Vec<Node<T, E>>,
+    roots: Versions<usize>,
+    sz: usize,
+}
+
+impl<T: Monoid, E: Effector<Target=T>> ArenaPersistentLazySegmentTree<T, E> {
+    pub fn new(n: usize) -> Self {
+        Self::from_vec(vec![T::identity(); n])
+    }
+    pub fn from_vec(vec: Vec<T>) -> Self {
+        let n = vec.len();
+        let mut seg = Self { nodes: Vec::with_capacity(2 * n), roots: Versions::new(NIL), sz: n };
+        let root = seg.build(0, n, &mut vec.into_iter());
+        seg.roots = Versions::new(root);
+        seg
+    }
+    fn alloc(&mut self, data: T, eff: E, left: usize, right: usize) -> usize {
+        self.nodes.push(Node { data, eff, left, right });
+        self.nodes.len() - 1
+    }
+    fn build<I: Iterator<Item=T>>(&mut self, l: usize, r: usize, iter: &mut I) -> usize {
+        if l + 1 == r { self.alloc(iter.next().unwrap(), E::identity(), NIL, NIL) }
+        else if l + 1 > r { self.alloc(T::identity(), E::identity(), NIL, NIL) }
+        else {
+            let left = self.build(l, (l + r) >> 1, iter);
+            let right = self.build((l + r) >> 1, r, iter);
+            let data = self.nodes[left].data.op(&self.nodes[right].data);
+            self.alloc(data, E::identity(), left, right)
+        }
+    }
+    fn root(&self, version: usize) -> usize {
+        *self.roots.get(version)
+    }
+    fn effect_node(&mut self, v: usize, (a, b): (usize, usize), new_eff: &E, l: usize, r: usize, fold_eff: E) -> usize {
+        let (left, right) = (self.nodes[v].left, self.nodes[v].right);
+        if a <= l && r <= b {
+            let eff = fold_eff.op(new_eff);
+            let data = eff.effect(&self.nodes[v].data, r - l);
+            let eff = self.nodes[v].eff.op(&eff);
+            self.alloc(data, eff, left, right)
+        }
+        else if r <= a || b <= l {
+            let data = fold_eff.effect(&self.nodes[v].data, r - l);
+            let eff = self.nodes[v].eff.op(&fold_eff);
+            self.alloc(data, eff, left, right)
+        }
+        else {
+            let m = (l + r) >> 1;
+            let eff = self.nodes[v].eff.op(&fold_eff);
+            let left = self.effect_node(left, (a, b), new_eff, l, m, eff.clone());
+            let right = self.effect_node(right, (a, b), new_eff, m, r, eff);
+            let data = self.nodes[left].data.op(&self.nodes[right].data);
+            self.alloc(data, E::identity(), left, right)
+        }
+    }
+    fn update_node(&mut self, v: usize, ups: &[(usize, T)], l: usize, r: usize, fold_eff: E) -> usize {
+        if ups.is_empty() {
+            let data = fold_eff.effect(&self.nodes[v].data, r - l);
+            let eff = self.nodes[v].eff.op(&fold_eff);
+            let (left, right) = (self.nodes[v].left, self.nodes[v].right);
+            self.alloc(data, eff, left, right)
+        }
+        else if l + 1 >= r { self.alloc(ups[ups.len() - 1].1.clone(), E::identity(), NIL, NIL) }
+        else {
+            let m = (l + r) >> 1;
+            let p = ups.partition_point(|u| u.0 < m);
+            let eff = self.nodes[v].eff.op(&fold_eff);
+            let (left, right) = (self.nodes[v].left, self.nodes[v].right);
+            let left = self.update_node(left, &ups[..p], l, m, eff.clone());
+            let right = self.update_node(right, &ups[p..], m, r, eff);
+            let data = self.nodes[left].data.op(&self.nodes[right].data);
+            self.alloc(data, E::identity(), left, right)
+        }
+    }
+    fn fold_node(&self, v: usize, a: usize, b: usize, l: usize, r: usize, eff: E) -> T {
+        if a <= l && r <= b { eff.effect(&self.nodes[v].data, r - l) }
+        else if r <= a || b <= l { T::identity() }
+        else {
+            let m = (l + r) >> 1;
+            let eff = self.nodes[v].eff.op(&eff);
+            self.fold_node(self.nodes[v].left, a, b, l, m, eff.clone())
+                .op(&self.fold_node(self.nodes[v].right, a, b, m, r, eff))
+        }
+    }
+
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        self.sz
+    }
+    pub fn contains(&self, version: usize) -> bool {
+        self.roots.contains(version)
+    }
+    pub fn latest(&self) -> usize {
+        self.roots.latest()
+    }
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+    pub fn update(&mut self, version: usize, i: usize, x: T) -> usize {
+        self.update_many(version, vec![(i, x)])
+    }
+    pub fn update_many<I: IntoIterator<Item=(usize, T)>>(&mut self, version: usize, iter: I) -> usize {
+        let root = self.root(version);
+        let mut ups: Vec<(usize, T)> = iter.into_iter().collect();
+        assert!(ups.iter().all(|u| u.0 < self.sz), "update_many(): out of range");
+        ups.sort_by_key(|u| u.0);
+        let root = self.update_node(root, &ups, 0, self.sz, E::identity());
+        self.roots.push(root)
+    }
+    pub fn effect_range(&mut self, version: usize, l: usize, r: usize, eff: E) -> usize {
+        let root = self.root(version);
+        assert!(l <= r && r <= self.sz, "effect_range(): out of range");
+        let root = self.effect_node(root, (l, r), &eff, 0, self.sz, E::identity());
+        self.roots.push(root)
+    }
+    pub fn fold<R: RangeBounds<usize>>(&self, version: usize, range: R) -> T {
+        let (l, r) = bounds(&range, self.sz);
+        self.fold_node(self.root(version), l, r, 0, self.sz, E::identity())
+    }
+    pub fn rollback(&mut self, version: usize) {
+        self.roots.rollback(version);
+    }
+    pub fn release(&mut self, version: usize) {
+        self.roots.release(version);
+    }
+    pub fn retain<F: FnMut(usize) -> bool>(&mut self, f: F) {
+        self.roots.retain(f);
+    }
+    pub fn compact(&mut self) {
+        self.roots.compact(&mut self.nodes);
+    }
+}
+
+#[cfg(test)]
+mod arena_persistent_lazy_segment_tree_test {
+    use segment_tree::arena_persistent_lazy_segment_tree::ArenaPersistentLazySegmentTree;
+    use algebra::laws::Rng;
+
+    monoid!(#[derive(Debug)] Sm, usize, 0, |a, b| a + b);
+
+    effector!(#[derive(Debug)] Uq, Option<usize>, None, |a, b| b.or(a), Sm, |e, t, sz| e.map_or(t.clone(), |u| Sm(u * sz)));
+
+    #[test]
+    fn random_test() {
+        let n = 11;
+        let mut seg = ArenaPersistentLazySegmentTree::from_vec((0..n).map(Sm).collect());
+        let mut arrays = vec![Some((0..n).collect::<Vec<usize>>())];
+        let mut rng = Rng::new(88172645463325252);
+        for q in 0..2000 {
+            let live: Vec<usize> = (0..arrays.len()).filter(|&v| arrays[v].is_some()).collect();
+            let v = live[rng.gen_index(live.len())];
+            match rng.gen_index(8) {
+                0 if live.len() > 1 => {
+                    seg.release(v);
+                    arrays[v] = None;
+                }
+                1 => {
+                    let ups: Vec<(usize, usize)> = (0..3).map(|_| (rng.gen_index(n), rng.gen_index(100))).collect();
+                    let w = seg.update_many(v, ups.iter().map(|&(i, y)| (i, Sm(y))));
+                    let mut arr = arrays[v].clone().unwrap();
+                    for &(i, y) in ups.iter() { arr[i] = y; }
+                    assert_eq!(w, arrays.len());
+                    arrays.push(Some(arr));
+                }
+                2..=4 => {
+                    let (a, b, y) = (rng.gen_index(n + 1), rng.gen_index(n + 1), rng.gen_index(100));
+                    let (l, r) = if a < b { (a, b) } else { (b, a) };
+                    let w = seg.effect_range(v, l, r, Uq(Some(y)));
+                    let mut arr = arrays[v].clone().unwrap();
+                    for e in arr[l..r].iter_mut() { *e = y; }
+                    assert_eq!(w, arrays.len());
+                    arrays.push(Some(arr));
+                }
+                _ => {
+                    let a = arrays[v].as_ref().unwrap();
+                    for l in 0..n + 1 {
+                        for r in l..n + 1 {
+                            assert_eq!(seg.fold(v, l..r).0, a[l..r].iter().sum::<usize>());
+                        }
+                    }
+                }
+            }
+            if q % 100 == 99 {
+                let before = seg.node_count();
+                seg.compact();
+                assert!(seg.node_count() <= before);
+            }
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use algebra::*;
+use segment_tree::bounds;
+use segment_tree::versions::{ Versions, ArenaNode, NIL };
+use std::ops::RangeBounds;
+
+struct Node<T: Monoid> {
+    data: T,
+    left: usize,
+    right: usize,
+}
+
+impl<T: Monoid> ArenaNode for Node<T> {
+    fn children(&mut self) -> (&mut usize, &mut usize) { (&mut self.left, &mut self.right) }
+}
+
+pub struct ArenaPersistentSegmentTree<T: Monoid> {
+    nodes: Vec<Node<T>>,
+    roots: Versions<usize>,
+    sz: usize,
+}
+
+impl<T: Monoid> ArenaPersistentSegmentTree<T> {
+    pub fn new(n: usize) -> Self {
+        Self::from_vec(vec![T::identity(); n])
+    }
+    pub fn from_vec(vec: Vec<T>) -> Self {
+        let n = vec.len();
+        let mut seg = Self { nodes: Vec::with_capacity(2 * n), roots: Versions::new(NIL), sz: n };
+        let root = seg.build(0, n, &mut vec.into_iter());
+        seg.roots = Versions::new(root);
+        seg
+    }
+    fn alloc(&mut self, data: T, left: usize, right: usize) -> usize {
+        self.nodes.push(Node { data, left, right });
+        self.nodes.len() - 1
+    }
+    fn build<I: Iterator<Item=T>>(&mut self, l: usize, r: usize, iter: &mut I) -> usize {
+        if l + 1 == r { self.alloc(iter.next().unwrap(), NIL, NIL) }
+        else if l + 1 > r { self.alloc(T::identity(), NIL, NIL) }
+        else {
+            let left = self.build(l, (l + r) >> 1, iter);
+            let right = self.build((l + r) >> 1, r, iter);
+            let data = self.nodes[left].data.op(&self.nodes[right].data);
+            self.alloc(data, left, right)
+        }
+    }
+    fn root(&self, version: usize) -> usize {
+        *self.roots.get(version)
+    }
+    fn update_node(&mut self, v: usize, i: usize, x: T, l: usize, r: usize) -> usize {
+        if l + 1 >= r { return self.alloc(x, NIL, NIL); }
+        let m = (l + r) >> 1;
+        let (mut left, mut right) = (self.nodes[v].left, self.nodes[v].right);
+        if i < m { left = self.update_node(left, i, x, l, m); }
+        else { right = self.update_node(right, i, x, m, r); }
+        let data = self.nodes[left].data.op(&self.nodes[right].data);
+        self.alloc(data, left, right)
+    }
+    fn update_many_node(&mut self, v: usize, ups: &[(usize, T)], l: usize, r: usize) -> usize {
+        if ups.is_empty() { return v; }
+        if l + 1 >= r { return self.alloc(ups[ups.len() - 1].1.clone(), NIL, NIL); }
+        let m = (l + r) >> 1;
+        let p = ups.partition_point(|u| u.0 < m);
+        let (left, right) = (self.nodes[v].left, self.nodes[v].right);
+        let left = self.update_many_node(left, &ups[..p], l, m);
+        let right = self.update_many_node(right, &ups[p..], m, r);
+        let data = self.nodes[left].data.op(&self.nodes[right].data);
+        self.alloc(data, left, right)
+    }
+    fn fold_node(&self, v: usize, a: usize, b: usize, l: usize, r: usize) -> T {
+        if a <= l && r <= b { self.nodes[v].data.clone() }
+        else if r <= a || b <= l { T::identity() }
+        else {
+            let m = (l + r) >> 1;
+            self.fold_node(self.nodes[v].left, a, b, l, m)
+                .op(&self.fold_node(self.nodes[v].right, a, b, m, r))
+        }
+    }
+
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        self.sz
+    }
+    pub fn contains(&self, version: usize) -> bool {
+        self.roots.contains(version)
+    }
+    pub fn latest(&self) -> usize {
+        self.roots.latest()
+    }
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+    pub fn update(&mut self, version: usize, i: usize, x: T) -> usize {
+        let root = self.root(version);
+        assert!(i < self.sz, "update(): out of range");
+        let root = self.update_node(root, i, x, 0, self.sz);
+        self.roots.push(root)
+    }
+    pub fn update_many<I: IntoIterator<Item=(usize, T)>>(&mut self, version: usize, iter: I) -> usize {
+        let root = self.root(version);
+        let mut ups: Vec<(usize, T)> = iter.into_iter().collect();
+        assert!(ups.iter().all(|u| u.0 < self.sz), "update_many(): out of range");
+        ups.sort_by_key(|u| u.0);
+        let root = self.update_many_node(root, &ups, 0, self.sz);
+        self.roots.push(root)
+    }
+    pub fn fold<R: RangeBounds<usize>>(&self, version: usize, range: R) -> T {
+        let (l, r) = bounds(&range, self.sz);
+        self.fold_node(self.root(version), l, r, 0, self.sz)
+    }
+    pub fn rollback(&mut self, version: usize) {
+        self.roots.rollback(version);
+    }
+    pub fn release(&mut self, version: usize) {
+        self.roots.release(version);
+    }
+    pub fn retain<F: FnMut(usize) -> bool>(&mut self, f: F) {
+        self.roots.retain(f);
+    }
+    pub fn compact(&mut self) {
+        self.roots.compact(&mut self.nodes);
+    }
+}
+
+#[cfg(test)]
+mod arena_persistent_segment_tree_test {
+    use segment_tree::arena_persistent_segment_tree::ArenaPersistentSegmentTree;
+    use algebra::laws::Rng;
+
+    monoid!(#[derive(Debug)] Am, usize, 0, |a, b| a + b);
+
+    #[test]
+    fn rollback_test() {
+        let mut seg = ArenaPersistentSegmentTree::from_vec(vec![Am(1), Am(2), Am(3)]);
+        let v1 = seg.update(0, 0, Am(10));
+        let v2 = seg.update(v1, 2, Am(30));
+        seg.rollback(v1);
+        assert!(!seg.contains(v2));
+        assert_eq!(seg.latest(), v1);
+        seg.compact();
+        let v3 = seg.update(0, 1, Am(20));
+        assert!(v3 > v2);
+        assert_eq!(seg.fold(v1, ..).0, 15);
+        assert_eq!(seg.fold(v3, ..).0, 24);
+    }
+
+    #[test]
+    fn random_test() {
+        let n = 13;
+        let mut seg = ArenaPersistentSegmentTree::from_vec((0..n).map(Am).collect());
+        let mut arrays = vec![Some((0..n).collect::<Vec<usize>>())];
+        let mut rng = Rng::new(88172645463325252);
+        for q in 0..2000 {
+            let live: Vec<usize> = (0..arrays.len()).filter(|&v| arrays[v].is_some()).collect();
+            let v = live[rng.gen_index(live.len())];
+            match rng.gen_index(8) {
+                0 if live.len() > 1 => {
+                    seg.release(v);
+                    arrays[v] = None;
+                }
+                1 => {
+                    let ups: Vec<(usize, usize)> = (0..3).map(|_| (rng.gen_index(n), rng.gen_index(100))).collect();
+                    let w = seg.update_many(v, ups.iter().map(|&(i, y)| (i, Am(y))));
+                    let mut a = arrays[v].clone().unwrap();
+                    for &(i, y) in ups.iter() { a[i] = y; }
+                    assert_eq!(w, arrays.len());
+                    arrays.push(Some(a));
+                }
+                2..=4 => {
+                    let (i, y) = (rng.gen_index(n), rng.gen_index(100));
+                    let w = seg.update(v, i, Am(y));
+                    let mut a = arrays[v].clone().unwrap();
+                    a[i] = y;
+                    assert_eq!(w, arrays.len());
+                    arrays.push(Some(a));
+                }
+                _ => {
+                    let a = arrays[v].as_ref().unwrap();
+                    for l in 0..n + 1 {
+                        for r in l..n + 1 {
+                            assert_eq!(seg.fold(v, l..r).0, a[l..r].iter().sum::<usize>());
+                        }
+                    }
+                }
+            }
+            if q % 100 == 99 {
+                let before = seg.node_count();
+                seg.compact();
+                assert!(seg.node_count() <= before);
+            }
+        }
+    }
+}
@@ -0,0 +1,166 @@
+use algebra::*;
+use segment_tree::{ RangeFold, bounds };
+use std::ops::RangeBounds;
+
+type Link<T, E> = Option<Box<Node<T, E>>>;
+
+struct Node<T: Monoid, E: Effector<Target=T>> {
+    data: T,
+    eff: E,
+    left: Link<T, E>,
+    right: Link<T, E>,
+}
+
+fn fold_link<T: Monoid, E: Effector<Target=T>>(link: &Link<T, E>) -> T {
+    match link {
+        Some(node) => node.data.clone(),
+        None => T::identity(),
+    }
+}
+
+impl<T: Monoid, E: Effector<Target=T>> Node<T, E> {
+    fn new() -> Self {
+        Node { data: T::identity(), eff: E::identity(), left: None, right: None }
+    }
+
+    fn apply(&mut self, e: &E, sz: usize) {
+        self.data = e.effect(&self.data, sz);
+        self.eff = self.eff.op(e);
+    }
+
+    fn push(&mut self, l: usize, r: usize) {
+        let m = (l + r) >> 1;
+        let eff = std::mem::replace(&mut self.eff, E::identity());
+        self.left.get_or_insert_with(|| Box::new(Node::new())).apply(&eff, m - l);
+        self.right.get_or_insert_with(|| Box::new(Node::new())).apply(&eff, r - m);
+    }
+
+    fn effect_range(link: &mut Link<T, E>, a: usize, b: usize, e: &E, l: usize, r: usize) {
+        if b <= l || r <= a { return; }
+        let node = link.get_or_insert_with(|| Box::new(Node::new()));
+        if a <= l && r <= b {
+            node.apply(e, r - l);
+        }
+        else {
+            let m = (l + r) >> 1;
+            node.push(l, r);
+            Node::effect_range(&mut node.left, a, b, e, l, m);
+            Node::effect_range(&mut node.right, a, b, e, m, r);
+            node.data = fold_link(&node.left).op(&fold_link(&node.right));
+        }
+    }
+
+    fn update(link: &mut Link<T, E>, i: usize, x: T, l: usize, r: usize) {
+        let node = link.get_or_insert_with(|| Box::new(Node::new()));
+        if l + 1 == r {
+            node.data = x;
+            node.eff = E::identity();
+        }
+        else {
+            let m = (l + r) >> 1;
+            node.push(l, r);
+            if i < m { Node::update(&mut node.left, i, x, l, m); }
+            else { Node::update(&mut node.right, i, x, m, r); }
+            node.data = fold_link(&node.left).op(&fold_link(&node.right));
+        }
+    }
+
+    fn fold(link: &Link<T, E>, a: usize, b: usize, l: usize, r: usize, eff: &E) -> T {
+        if b <= l || r <= a { return T::identity(); }
+        match link {
+            Some(node) => {
+                if a <= l && r <= b { eff.effect(&node.data, r - l) }
+                else {
+                    let m = (l + r) >> 1;
+                    let eff = node.eff.op(eff);
+                    Node::fold(&node.left, a, b, l, m, &eff)
+                        .op(&Node::fold(&node.right, a, b, m, r, &eff))
+                }
+            }
+            None => {
+                eff.effect(&T::identity(), std::cmp::min(b, r) - std::cmp::max(a, l))
+            }
+        }
+    }
+}
+
+pub struct DynamicLazySegmentTree<T: Monoid, E: Effector<Target=T>> {
+    root: Link<T, E>,
+    sz: usize,
+    len: usize,
+}
+
+impl<T: Monoid, E: Effector<Target=T>> DynamicLazySegmentTree<T, E> {
+    pub fn new(len: usize) -> Self {
+        let sz = len.checked_next_power_of_two().expect("new(): length too large");
+        DynamicLazySegmentTree {
+            root: None,
+            sz,
+            len,
+        }
+    }
+    pub fn update(&mut self, i: usize, x: T) {
+        assert!(i < self.len, "update(): out of range");
+        Node::update(&mut self.root, i, x, 0, self.sz);
+    }
+    pub fn effect_range(&mut self, l: usize, r: usize, e: E) {
+        assert!(l <= r && r <= self.len, "effect_range(): out of range");
+        Node::effect_range(&mut self.root, l, r, &e, 0, self.sz);
+    }
+    pub fn fold<R: RangeBounds<usize>>(&self, range: R) -> T {
+        let (l, r) = bounds(&range, self.len);
+        Node::fold(&self.root, l, r, 0, self.sz, &E::identity())
+    }
+}
+
+impl<T: Monoid, E: Effector<Target=T>> RangeFold for DynamicLazySegmentTree<T, E> {
+    type Value = T;
+    fn fold<R: RangeBounds<usize>>(&self, range: R) -> T { DynamicLazySegmentTree::fold(self, range) }
+}
+
+#[cfg(test)]
+mod dynamic_lazy_segment_tree_test {
+    use segment_tree::dynamic_lazy_segment_tree::DynamicLazySegmentTree;
+    use std::cmp::min;
+
+    monoid!(Mm, usize, usize::MAX, |a, b| min(a, b));
+
+    effector!(Uq, Option<usize>, None, |a, b| b.or(a), Mm, |e, t, _sz| Mm(e.unwrap_or(t.0)));
+
+    #[test]
+    fn rmq_ruq_test() {
+        let mut seg = DynamicLazySegmentTree::new(1_000_000_000_000_000_000);
+        seg.effect_range(0, 2, Uq(Some(1)));
+        seg.effect_range(1, 3, Uq(Some(3)));
+        seg.effect_range(2, 3, Uq(Some(2)));
+        assert_eq!(seg.fold(0..3).0, 1);
+        assert_eq!(seg.fold(1..3).0, 2);
+        assert_eq!(seg.fold(3..).0, usize::MAX);
+        seg.update(10, Mm(5));
+        assert_eq!(seg.fold(1..100).0, 2);
+        assert_eq!(seg.fold(3..100).0, 5);
+    }
+
+    monoid!(#[derive(Debug)] Sm, usize, 0, |a, b| a + b);
+
+    effector!(#[derive(Debug)] Aq, usize, 0, |a, b| a + b, Sm, |e, t, s| Sm(t.0 + e * s));
+
+    #[test]
+    fn rsq_raq_test() {
+        let n = 1_000_000_000_000_000_000;
+        let mut seg = DynamicLazySegmentTree::new(n);
+        seg.effect_range(0, n, Aq(1));
+        seg.effect_range(n / 2, n, Aq(2));
+        seg.update(0, Sm(10));
+        assert_eq!(seg.fold(..).0, 10 + (n - 1) + n);
+        assert_eq!(seg.fold(n / 2 - 5..n / 2 + 5).0, 5 + 15);
+        assert_eq!(seg.fold(..=2).0, 12);
+    }
+
+    #[test]
+    #[should_panic(expected = "out of range")]
+    fn out_of_range_test() {
+        let mut seg = DynamicLazySegmentTree::new(5);
+        seg.effect_range(3, 6, Aq(1));
+    }
+}
@@ -1,16 +1,6 @@
-pub trait Magma: Sized + Clone {
-  fn op(&self, rhs: &Self) -> Self;
-}
-
-pub trait Associative: Magma {}
-
-pub trait Unital: Magma {
-  fn identity() -> Self;
-}
-
-pub trait Monoid: Magma + Associative + Unital {}
-
-impl<T: Magma + Associative + Unital> Monoid for T {}
+use algebra::*;
+use segment_tree::{ RangeFold, bounds };
+use std::ops::RangeBounds;
 
 pub enum Node<T: Monoid> {
     Section(Box<Section<T>>),
@@ -31,7 +21,7 @@ pub struct Leaf<T: Monoid> {
 
 impl<T: Monoid> Leaf<T> {
     fn new(i: usize, x: T) -> Self {
-        Leaf { i: i, val: x }
+        Leaf { i, val: x }
     }
     fn fold(&self) -> T { self.val.clone() }
 }
@@ -65,9 +55,9 @@ impl<T: Monoid> Node<T> {
     }
     fn fold(&self) -> T {
         match self {
-            &Node::Section(ref sec) => sec.as_ref().fold(),
-            &Node::Leaf(ref leaf) => leaf.fold(),
-            &Node::None => T::identity(),
+            Node::Section(sec) => sec.as_ref().fold(),
+            Node::Leaf(leaf) => leaf.fold(),
+            Node::None => T::identity(),
         }
     }
     fn update(self, i: usize, x: T, l: usize, r: usize) -> Self {
@@ -100,7 +90,7 @@ impl<T: Monoid> Node<T> {
     }
     fn range_fold(&self, a: usize, b: usize, l: usize, r: usize) -> T {
         match self {
-            &Node::Section(ref sec) => {
+            Node::Section(sec) => {
                 if b <= l || r <= a { T::identity() }
                 else if a <= l && r <= b { sec.fold() }
                 else {
@@ -108,34 +98,144 @@ impl<T: Monoid> Node<T> {
                     sec.left.range_fold(a, b, l, m).op(&sec.right.range_fold(a, b, m, r))
                 }
             }
-            &Node::Leaf(ref leaf) => {
+            Node::Leaf(leaf) => {
                 if a <= leaf.i && leaf.i < b { leaf.fold() }
                 else { T::identity() }
             }
-            &Node::None => T::identity(),
+            Node::None => T::identity(),
+        }
+    }
+    fn max_right<F: Fn(&T) -> bool>(&self, a: usize, l: usize, r: usize, pred: &F, acc: &mut T) -> usize {
+        if r <= a { return r; }
+        match self {
+            Node::Section(sec) => {
+                if a <= l {
+                    let nx = acc.op(&sec.val);
+                    if pred(&nx) {
+                        *acc = nx;
+                        return r;
+                    }
+                }
+                let m = (l + r) >> 1;
+                let x = sec.left.max_right(a, l, m, pred, acc);
+                if x < m { x }
+                else { sec.right.max_right(a, m, r, pred, acc) }
+            }
+            Node::Leaf(leaf) => {
+                if leaf.i < a { return r; }
+                let nx = acc.op(&leaf.val);
+                if pred(&nx) {
+                    *acc = nx;
+                    r
+                }
+                else { leaf.i }
+            }
+            Node::None => r,
+        }
+    }
+    fn min_left<F: Fn(&T) -> bool>(&self, b: usize, l: usize, r: usize, pred: &F, acc: &mut T) -> usize {
+        if b <= l { return l; }
+        match self {
+            Node::Section(sec) => {
+                if r <= b {
+                    let nx = sec.val.op(acc);
+                    if pred(&nx) {
+                        *acc = nx;
+                        return l;
+                    }
+                }
+                let m = (l + r) >> 1;
+                let x = sec.right.min_left(b, m, r, pred, acc);
+                if x > m { x }
+                else { sec.left.min_left(b, l, m, pred, acc) }
+            }
+            Node::Leaf(leaf) => {
+                if b <= leaf.i { return l; }
+                let nx = leaf.val.op(acc);
+                if pred(&nx) {
+                    *acc = nx;
+                    l
+                }
+                else { leaf.i + 1 }
+            }
+            Node::None => l,
         }
     }
 }
 
 pub struct DynamicSegmentTree<T: Monoid> {
     root: Node<T>,
-    n: usize,
+    sz: usize,
+    len: usize,
 }
 
 impl<T: Monoid> DynamicSegmentTree<T> {
-    pub fn new(sz: usize) -> Self {
-        let mut n = 1;
-        while n < sz { n = n << 1; }
+    pub fn new(len: usize) -> Self {
+        let sz = len.checked_next_power_of_two().expect("new(): length too large");
         DynamicSegmentTree {
             root: Node::None,
-            n: n,
+            sz,
+            len,
         }
     }
     pub fn update(&mut self, i: usize, x: T) {
+        assert!(i < self.len, "update(): out of range");
         let r = self.root.take();
-        self.root = r.update(i, x, 0,  self.n);
+        self.root = r.update(i, x, 0, self.sz);
+    }
+    pub fn fold<R: RangeBounds<usize>>(&self, range: R) -> T {
+        let (l, r) = bounds(&range, self.len);
+        self.root.range_fold(l, r, 0, self.sz)
+    }
+    pub fn max_right<F: Fn(&T) -> bool>(&self, l: usize, pred: F) -> usize {
+        assert!(l <= self.len, "max_right(): out of range");
+        std::cmp::min(self.root.max_right(l, 0, self.sz, &pred, &mut T::identity()), self.len)
+    }
+    pub fn min_left<F: Fn(&T) -> bool>(&self, r: usize, pred: F) -> usize {
+        assert!(r <= self.len, "min_left(): out of range");
+        self.root.min_left(r, 0, self.sz, &pred, &mut T::identity())
+    }
+}
+
+impl<T: Monoid> RangeFold for DynamicSegmentTree<T> {
+    type Value = T;
+    fn fold<R: RangeBounds<usize>>(&self, range: R) -> T { DynamicSegmentTree::fold(self, range) }
+}
+
+#[cfg(test)]
+mod rsq_test {
+    use segment_tree::dynamic_segment_tree::DynamicSegmentTree;
+
+    monoid!(Am, usize, 0, |a, b| a + b);
+
+    #[test]
+    fn rsq_test() {
+        let mut seg = DynamicSegmentTree::new(1_000_000_000_000_000_000);
+        seg.update(3, Am(1));
+        seg.update(999_999_999_999_999_999, Am(2));
+        seg.update(500_000_000_000, Am(3));
+        seg.update(3, Am(4));
+        assert_eq!(seg.fold(..).0, 9);
+        assert_eq!(seg.fold(0..4).0, 4);
+        assert_eq!(seg.fold(4..500_000_000_000).0, 0);
+        assert_eq!(seg.fold(4..999_999_999_999_999_999).0, 3);
+        assert_eq!(seg.fold(4..).0, 5);
     }
-    pub fn fold(&self, l: usize, r: usize) -> T {
-        self.root.range_fold(l, r, 0, self.n)
+    #[test]
+    fn binary_search_test() {
+        let n = 1_000_000_000_000_000_000;
+        let mut seg = DynamicSegmentTree::new(n);
+        seg.update(10, Am(1));
+        seg.update(20, Am(2));
+        seg.update(1_000_000_000, Am(3));
+        assert_eq!(seg.max_right(0, |x| x.0 == 0), 10);
+        assert_eq!(seg.max_right(0, |x| x.0 <= 1), 20);
+        assert_eq!(seg.max_right(11, |x| x.0 <= 4), 1_000_000_000);
+        assert_eq!(seg.max_right(11, |x| x.0 <= 5), n);
+        assert_eq!(seg.min_left(n, |x| x.0 == 0), 1_000_000_001);
+        assert_eq!(seg.min_left(n, |x| x.0 <= 5), 11);
+        assert_eq!(seg.min_left(20, |x| x.0 <= 1), 0);
+        assert_eq!(seg.min_left(21, |x| x.0 <= 1), 21);
+        assert_eq!(seg.min_left(21, |x| x.0 <= 2), 11);
     }
 }
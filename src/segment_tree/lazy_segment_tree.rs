@@ -1,113 +1,298 @@
 use algebra::*;
+use segment_tree::{ RangeFold, bounds };
+use std::ops::RangeBounds;
 
 pub struct LazySegmentTree<T: Monoid, E: Effector<Target=T>> {
     node: Vec<T>,
     lazy: Vec<E>,
     sz: usize,
+    log: usize,
+    n: usize,
 }
 
 impl<T: Monoid, E: Effector<Target=T>> LazySegmentTree<T, E> {
     pub fn init(vec: Vec<T>) -> Self {
         let mut sz = 1;
-        while sz < vec.len() { sz *= 2 }
+        let mut log = 0;
+        while sz < vec.len() {
+            sz *= 2;
+            log += 1;
+        }
         let mut node = vec![T::identity(); sz << 1];
-        let lazy = vec![E::identity(); sz << 1];
-        for i in 0..vec.len() { node[i + sz] = vec[i].clone(); }
+        let lazy = vec![E::identity(); sz];
+        node[sz..(vec.len() + sz)].clone_from_slice(&vec);
         for i in (1..sz).rev() { node[i] = node[i << 1].op(&node[(i << 1) + 1]); }
-        Self { node: node, lazy: lazy, sz: sz }
+        Self { node, lazy, sz, log, n: vec.len() }
     }
 
-    fn push(&mut self, i: usize, sz: usize) {
-        self.node[i] = self.lazy[i].effect(&self.node[i], sz);
-        if (i << 1) < self.node.len() {
-            self.lazy[i << 1] = self.lazy[i << 1].op(&self.lazy[i]);
-            self.lazy[(i << 1) + 1] = self.lazy[(i << 1) + 1].op(&self.lazy[i]);
-        }
-        self.lazy[i] = E::identity();
+    fn width(&self, i: usize) -> usize {
+        let depth = (0usize.leading_zeros() - i.leading_zeros() - 1) as usize;
+        self.sz >> depth
+    }
+
+    fn apply(&mut self, i: usize, e: &E) {
+        self.node[i] = e.effect(&self.node[i], self.width(i));
+        if i < self.sz { self.lazy[i] = self.lazy[i].op(e); }
+    }
+
+    fn push(&mut self, i: usize) {
+        let e = std::mem::replace(&mut self.lazy[i], E::identity());
+        self.apply(i << 1, &e);
+        self.apply((i << 1) + 1, &e);
     }
 
-    fn update_raw(&mut self, i: usize, a: usize, b: usize, l: usize, r: usize, e: &E) {
-        self.push(i, r - l);
-        if b <= l || r <= a { return; }
-        else if a <= l && r <= b {
-            self.lazy[i] = self.lazy[i].op(e);
-            self.push(i, r - l);
+    fn fix(&mut self, i: usize) {
+        self.node[i] = self.node[i << 1].op(&self.node[(i << 1) + 1]);
+    }
+
+    pub fn update_range(&mut self, l: usize, r: usize, e: E) {
+        assert!(l <= r && r <= self.n, "update_range(): out of range");
+        if l == r { return; }
+        let l = l + self.sz;
+        let r = r + self.sz;
+        for i in (1..self.log + 1).rev() {
+            if ((l >> i) << i) != l { self.push(l >> i); }
+            if ((r >> i) << i) != r { self.push((r - 1) >> i); }
         }
-        else {
-            self.update_raw(i << 1, a, b, l, (l + r) >> 1, e);
-            self.update_raw((i << 1) + 1, a, b, (l + r) >> 1, r, e);
-            self.node[i] = self.node[i << 1].op(&self.node[(i << 1) + 1]);
+        {
+            let mut l = l;
+            let mut r = r;
+            while l < r {
+                if (l & 1) == 1 {
+                    self.apply(l, &e);
+                    l += 1;
+                }
+                if (r & 1) == 1 {
+                    r -= 1;
+                    self.apply(r, &e);
+                }
+                l >>= 1;
+                r >>= 1;
+            }
+        }
+        for i in 1..self.log + 1 {
+            if ((l >> i) << i) != l { self.fix(l >> i); }
+            if ((r >> i) << i) != r { self.fix((r - 1) >> i); }
         }
     }
 
-    pub fn update_range(&mut self, l: usize, r: usize, e: E) {
-        let sz = self.sz;
-        self.update_raw(1, l, r, 0, sz, &e);
+    pub fn set(&mut self, i: usize, x: T) {
+        assert!(i < self.n, "set(): out of range");
+        let i = i + self.sz;
+        for d in (1..self.log + 1).rev() { self.push(i >> d); }
+        self.node[i] = x;
+        for d in 1..self.log + 1 { self.fix(i >> d); }
     }
 
-    fn fold_raw(&mut self, i: usize, a: usize, b: usize, l: usize, r: usize) -> T {
-        self.push(i, r - l);
-        if b <= l || r <= a { T::identity() }
-        else if a <= l && r <= b { self.node[i].clone() }
-        else {
-            self.fold_raw(i << 1, a, b, l, (l + r) >> 1)
-                .op(&self.fold_raw((i << 1) + 1, a, b, (l + r) >> 1, r))
+    pub fn get(&self, i: usize) -> T {
+        assert!(i < self.n, "get(): out of range");
+        let i = i + self.sz;
+        let mut e = E::identity();
+        for d in (1..self.log + 1).rev() { e = self.lazy[i >> d].op(&e); }
+        e.effect(&self.node[i], 1)
+    }
+
+    pub fn to_vec(&self) -> Vec<T> {
+        // effs[i] is the composition of the effects pending on `i` and above it
+        let mut effs = self.lazy.clone();
+        for i in 2..self.sz { effs[i] = effs[i].op(&effs[i >> 1]); }
+        (self.sz..self.sz + self.n).map(|i| {
+            if i > 1 { effs[i >> 1].effect(&self.node[i], 1) }
+            else { self.node[i].clone() }
+        }).collect()
+    }
+
+    pub fn fold<R: RangeBounds<usize>>(&self, range: R) -> T {
+        let (l, r) = bounds(&range, self.n);
+        if l == r { return T::identity(); }
+        let mut l = l + self.sz;
+        let mut r = r + self.sz;
+        // el[d] and er[d] compose the effects pending above the nodes of width 1 << d
+        // taken at the left and right ends; those all lie on the two boundary paths
+        let mut el = vec![E::identity(); self.log + 1];
+        let mut er = vec![E::identity(); self.log + 1];
+        for d in (0..self.log).rev() {
+            el[d] = self.lazy[l >> (d + 1)].op(&el[d + 1]);
+            er[d] = self.lazy[(r - 1) >> (d + 1)].op(&er[d + 1]);
+        }
+        let mut sml = T::identity();
+        let mut smr = T::identity();
+        let mut d = 0;
+        while l < r {
+            if (l & 1) == 1 {
+                sml = sml.op(&el[d].effect(&self.node[l], 1 << d));
+                l += 1;
+            }
+            if (r & 1) == 1 {
+                r -= 1;
+                smr = er[d].effect(&self.node[r], 1 << d).op(&smr);
+            }
+            l >>= 1;
+            r >>= 1;
+            d += 1;
         }
+        sml.op(&smr)
     }
 
-    pub fn fold(&mut self, l: usize, r: usize) -> T {
-        let sz = self.sz;
-        self.fold_raw(1, l, r, 0, sz)
+    pub fn max_right<F: Fn(&T) -> bool>(&mut self, l: usize, pred: F) -> usize {
+        assert!(l <= self.n, "max_right(): out of range");
+        if l == self.n { return self.n; }
+        let mut l = l + self.sz;
+        for d in (1..self.log + 1).rev() { self.push(l >> d); }
+        let mut acc = T::identity();
+        loop {
+            while (l & 1) == 0 { l >>= 1; }
+            if !pred(&acc.op(&self.node[l])) {
+                while l < self.sz {
+                    self.push(l);
+                    l <<= 1;
+                    let nx = acc.op(&self.node[l]);
+                    if pred(&nx) {
+                        acc = nx;
+                        l += 1;
+                    }
+                }
+                return l - self.sz;
+            }
+            acc = acc.op(&self.node[l]);
+            l += 1;
+            if (l & l.wrapping_neg()) == l { return self.n; }
+        }
     }
+
+    pub fn min_left<F: Fn(&T) -> bool>(&mut self, r: usize, pred: F) -> usize {
+        assert!(r <= self.n, "min_left(): out of range");
+        if r == 0 { return 0; }
+        let mut r = r + self.sz;
+        for d in (1..self.log + 1).rev() { self.push((r - 1) >> d); }
+        let mut acc = T::identity();
+        loop {
+            r -= 1;
+            while r > 1 && (r & 1) == 1 { r >>= 1; }
+            if !pred(&self.node[r].op(&acc)) {
+                while r < self.sz {
+                    self.push(r);
+                    r = (r << 1) + 1;
+                    let nx = self.node[r].op(&acc);
+                    if pred(&nx) {
+                        acc = nx;
+                        r -= 1;
+                    }
+                }
+                return r + 1 - self.sz;
+            }
+            acc = self.node[r].op(&acc);
+            if (r & r.wrapping_neg()) == r { return 0; }
+        }
+    }
+}
+
+impl<T: Monoid, E: Effector<Target=T>> RangeFold for LazySegmentTree<T, E> {
+    type Value = T;
+    fn fold<R: RangeBounds<usize>>(&self, range: R) -> T { LazySegmentTree::fold(self, range) }
 }
 
 #[cfg(test)]
 mod rmq_ruq_test {
     use algebra::*;
     use segment_tree::lazy_segment_tree::LazySegmentTree;
+    use algebra::laws::Rng;
     use std::cmp::min;
 
-    #[derive(Clone)]
-    struct Mm(usize);
+    monoid!(Mm, usize, usize::MAX, |a, b| min(a, b));
 
-    impl Magma for Mm {
-        fn op(&self, right: &Self) -> Self { Mm(min(self.0, right.0)) }
-    }
-    impl Associative for Mm {}
-    impl Unital for Mm {
-        fn identity() -> Self { Mm(std::usize::MAX) }
+    effector!(Uq, Option<usize>, None, |a, b| b.or(a), Mm, |e, t, _sz| Mm(e.unwrap_or(t.0)));
+
+    #[test]
+    fn rmq_ruq_test() {
+        let mut seg = LazySegmentTree::init(vec![Mm::identity(); 3]);
+        seg.update_range(0, 2, Uq(Some(1)));
+        seg.update_range(1, 3, Uq(Some(3)));
+        seg.update_range(2, 3, Uq(Some(2)));
+        assert_eq!(seg.fold(0..3).0, 1);
+        assert_eq!(seg.fold(1..3).0, 2);
     }
 
-    #[derive(Clone)]
-    struct Uq(Option<usize>);
+    #[test]
+    fn point_set_get_test() {
+        let mut seg = LazySegmentTree::init(vec![Mm(5), Mm(4), Mm(3), Mm(2), Mm(1)]);
+        seg.update_range(0, 4, Uq(Some(7)));
+        seg.set(2, Mm(0));
+        assert_eq!(seg.get(1).0, 7);
+        assert_eq!(seg.get(2).0, 0);
+        assert_eq!(seg.fold(0..2).0, 7);
+        assert_eq!(seg.fold(..).0, 0);
+        seg.update_range(2, 5, Uq(Some(6)));
+        seg.set(0, Mm(9));
+        assert_eq!(seg.fold(0..2).0, 7);
+        assert!(seg.to_vec().iter().map(|x| x.0).eq(vec![9, 7, 6, 6, 6]));
+    }
 
-    impl Magma for Uq {
-        fn op(&self, right: &Self) -> Self {
-            if right.0.is_none() { self.clone() }
-            else { right.clone() }
+    #[test]
+    fn random_test() {
+        let n = 37;
+        let mut model: Vec<usize> = (0..n).map(|i| (i * 7) % 13).collect();
+        let mut seg = LazySegmentTree::init(model.iter().map(|&x| Mm(x)).collect());
+        let mut rng = Rng::new(1);
+        for _ in 0..3000 {
+            let (a, b) = (rng.gen_index(n + 1), rng.gen_index(n + 1));
+            let (l, r2) = if a < b { (a, b) } else { (b, a) };
+            let v = rng.gen_index(50);
+            match rng.gen_index(4) {
+                0 => {
+                    seg.update_range(l, r2, Uq(Some(v)));
+                    for y in model[l..r2].iter_mut() { *y = v; }
+                }
+                1 if l < n => {
+                    seg.set(l, Mm(v));
+                    model[l] = v;
+                }
+                2 => {
+                    let expected = model[l..].iter().scan(usize::MAX, |m, &y| { *m = min(*m, y); Some(*m) })
+                        .take_while(|&m| m >= v).count() + l;
+                    assert_eq!(seg.max_right(l, |m| m.0 >= v), expected);
+                    let expected = r2 - model[..r2].iter().rev().scan(usize::MAX, |m, &y| { *m = min(*m, y); Some(*m) })
+                        .take_while(|&m| m >= v).count();
+                    assert_eq!(seg.min_left(r2, |m| m.0 >= v), expected);
+                }
+                _ => {
+                    assert_eq!(seg.fold(l..r2).0, model[l..r2].iter().cloned().min().unwrap_or(usize::MAX));
+                }
+            }
         }
+        assert!(seg.to_vec().iter().map(|x| x.0).eq(model.iter().cloned()));
     }
-    impl Associative for Uq {}
-    impl Unital for Uq {
-        fn identity() -> Self { Uq(None) }
-    }
-    impl Effector for Uq {
-        type Target = Mm;
-        fn effect(&self, t: &Self::Target, _: usize) -> Self::Target {
-            match self.0 {
-                Some(u) => Mm(u),
-                None => t.clone(),
+
+    #[test]
+    fn power_of_two_test() {
+        for &n in [1, 2, 4, 8].iter() {
+            let mut model: Vec<usize> = (0..n).map(|i| 10 + i).collect();
+            let mut seg = LazySegmentTree::init(model.iter().map(|&x| Mm(x)).collect());
+            assert_eq!(seg.fold(..).0, 10);
+            seg.update_range(n / 2, n, Uq(Some(3)));
+            for y in model[n / 2..].iter_mut() { *y = 3; }
+            for l in 0..n + 1 {
+                for r in l..n + 1 {
+                    assert_eq!(seg.fold(l..r).0, model[l..r].iter().cloned().min().unwrap_or(usize::MAX));
+                }
             }
+            for (i, &y) in model.iter().enumerate() { assert_eq!(seg.get(i).0, y); }
+            assert!(seg.to_vec().iter().map(|x| x.0).eq(model.iter().cloned()));
         }
     }
 
     #[test]
-    fn rmq_ruq_test() {
-        let mut seg = LazySegmentTree::init(vec![Mm::identity(); 3]);
-        seg.update_range(0, 2, Uq(Some(1)));
-        seg.update_range(1, 3, Uq(Some(3)));
-        seg.update_range(2, 3, Uq(Some(2)));
-        assert_eq!(seg.fold(0, 3).0, 1);
-        assert_eq!(seg.fold(1, 3).0, 2);
+    fn binary_search_test() {
+        let mut seg = LazySegmentTree::init(vec![Mm(5), Mm(4), Mm(3), Mm(2), Mm(1)]);
+        seg.update_range(1, 4, Uq(Some(7)));
+        assert_eq!(seg.max_right(0, |x| x.0 >= 5), 4);
+        assert_eq!(seg.max_right(1, |x| x.0 >= 7), 4);
+        assert_eq!(seg.max_right(2, |x| x.0 >= 1), 5);
+        assert_eq!(seg.max_right(5, |x| x.0 >= 8), 5);
+        assert_eq!(seg.min_left(5, |x| x.0 >= 2), 5);
+        assert_eq!(seg.min_left(4, |x| x.0 >= 7), 1);
+        assert_eq!(seg.min_left(4, |x| x.0 >= 5), 0);
+        assert_eq!(seg.min_left(4, |x| x.0 >= 6), 1);
+        assert_eq!(seg.min_left(0, |x| x.0 >= 8), 0);
     }
 }
@@ -1,5 +1,34 @@
+#[allow(clippy::module_inception)]
 pub mod segment_tree;
 pub mod persistent_segment_tree;
 pub mod lazy_segment_tree;
 pub mod persistent_lazy_segment_tree;
 pub mod dynamic_segment_tree;
+pub mod dynamic_lazy_segment_tree;
+pub mod versioned_segment_tree;
+pub mod arena_persistent_segment_tree;
+pub mod arena_persistent_lazy_segment_tree;
+mod versions;
+
+use algebra::Monoid;
+use std::ops::{ Bound, RangeBounds };
+
+pub trait RangeFold {
+    type Value: Monoid;
+    fn fold<R: RangeBounds<usize>>(&self, range: R) -> Self::Value;
+}
+
+pub fn bounds<R: RangeBounds<usize>>(range: &R, n: usize) -> (usize, usize) {
+    let l = match range.start_bound() {
+        Bound::Included(&l) => l,
+        Bound::Excluded(&l) => l + 1,
+        Bound::Unbounded => 0,
+    };
+    let r = match range.end_bound() {
+        Bound::Included(&r) => r + 1,
+        Bound::Excluded(&r) => r,
+        Bound::Unbounded => n,
+    };
+    assert!(l <= r && r <= n, "fold(): out of range");
+    (l, r)
+}
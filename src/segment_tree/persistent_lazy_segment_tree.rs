@@ -1,4 +1,6 @@
 use algebra::*;
+use segment_tree::{ RangeFold, bounds };
+use std::ops::RangeBounds;
 
 use std::rc::Rc;
 
@@ -13,7 +15,7 @@ struct Node<T: Monoid, E: Effector<Target=T>> {
 
 impl<T: Monoid, E: Effector<Target=T>> Node<T, E> {
     fn new(data: T) -> Self {
-        Node { data: data, eff: E::identity(), left: None, right: None }
+        Node { data, eff: E::identity(), left: None, right: None }
     }
     fn build(l: usize, r: usize) -> Self {
         if l + 1 >= r { Node::new(T::identity()) }
@@ -24,8 +26,22 @@ impl<T: Monoid, E: Effector<Target=T>> Node<T, E> {
                 data: match left.as_ref() { Some(n) => n.data.clone(), None => T::identity() }
                       .op(& match right.as_ref() { Some(n) => n.data.clone(), None => T::identity() }),
                 eff: E::identity(),
-                left: left,
-                right: right,
+                left,
+                right,
+            }
+        }
+    }
+    fn build_from<I: Iterator<Item=T>>(l: usize, r: usize, iter: &mut I) -> Self {
+        if l + 1 == r { Node::new(iter.next().unwrap()) }
+        else if l + 1 > r { Node::new(T::identity()) }
+        else {
+            let left = Node::<T, E>::build_from(l, (l + r) >> 1, iter);
+            let right = Node::<T, E>::build_from((l + r) >> 1, r, iter);
+            Node {
+                data: left.data.op(&right.data),
+                eff: E::identity(),
+                left: Some(Rc::new(left)),
+                right: Some(Rc::new(right)),
             }
         }
     }
@@ -55,8 +71,33 @@ impl<T: Monoid, E: Effector<Target=T>> Node<T, E> {
                 data: match left.as_ref() { Some(n) => n.data.clone(), None => T::identity() }
                       .op(& match right.as_ref() { Some(n) => n.data.clone(), None => T::identity() }),
                 eff: E::identity(),
-                left: left,
-                right: right,
+                left,
+                right,
+            }
+        }
+    }
+
+    fn update_many(&self, ups: &[(usize, T)], l: usize, r: usize, fold_eff: E) -> Self {
+        if ups.is_empty() {
+            Node {
+                data: fold_eff.effect(&self.data, r - l),
+                eff: self.eff.op(&fold_eff),
+                left: self.left.clone(),
+                right: self.right.clone(),
+            }
+        }
+        else if l + 1 >= r { Node::new(ups[ups.len() - 1].1.clone()) }
+        else {
+            let m = (l + r) >> 1;
+            let p = ups.partition_point(|u| u.0 < m);
+            let eff = self.eff.op(&fold_eff);
+            let left = self.left.as_ref().unwrap().update_many(&ups[..p], l, m, eff.clone());
+            let right = self.right.as_ref().unwrap().update_many(&ups[p..], m, r, eff);
+            Node {
+                data: left.data.op(&right.data),
+                eff: E::identity(),
+                left: Some(Rc::new(left)),
+                right: Some(Rc::new(right)),
             }
         }
     }
@@ -74,10 +115,10 @@ impl<T: Monoid, E: Effector<Target=T>> Node<T, E> {
 impl<T: Monoid, E: Effector<Target=T>> Drop for Node<T, E> {
     fn drop(&mut self) {
         if let Some(left) = self.left.take() {
-            if let Ok(_) = Rc::try_unwrap(left) {}
+            let _ = Rc::try_unwrap(left);
         }
         if let Some(right) = self.right.take() {
-            if let Ok(_) = Rc::try_unwrap(right) {}
+            let _ = Rc::try_unwrap(right);
         }
     }
 }
@@ -92,53 +133,41 @@ impl<T: Monoid, E: Effector<Target=T>> PersistentLazySegmentTree<T, E> {
     pub fn new(n: usize) -> Self {
         Self { root: Node::build(0, n), sz: n }
     }
+    pub fn from_vec(vec: Vec<T>) -> Self {
+        let n = vec.len();
+        Self { root: Node::build_from(0, n, &mut vec.into_iter()), sz: n }
+    }
+    pub fn update(&self, i: usize, x: T) -> Self {
+        self.update_many(vec![(i, x)])
+    }
+    pub fn update_many<I: IntoIterator<Item=(usize, T)>>(&self, iter: I) -> Self {
+        let mut ups: Vec<(usize, T)> = iter.into_iter().collect();
+        assert!(ups.iter().all(|u| u.0 < self.sz), "update_many(): out of range");
+        ups.sort_by_key(|u| u.0);
+        Self { root: self.root.update_many(&ups, 0, self.sz, E::identity()), sz: self.sz }
+    }
     pub fn effect_range(&self, l: usize, r: usize, eff: E) -> Self {
         Self { root: self.root.effect_range(l, r, eff, 0, self.sz, E::identity()), sz: self.sz }
     }
-    pub fn fold(&self, l: usize, r: usize) -> T {
+    pub fn fold<R: RangeBounds<usize>>(&self, range: R) -> T {
+        let (l, r) = bounds(&range, self.sz);
         self.root.fold(l, r, 0, self.sz, E::identity())
     }
 }
 
+impl<T: Monoid, E: Effector<Target=T>> RangeFold for PersistentLazySegmentTree<T, E> {
+    type Value = T;
+    fn fold<R: RangeBounds<usize>>(&self, range: R) -> T { PersistentLazySegmentTree::fold(self, range) }
+}
+
 #[cfg(test)]
 mod persistent_lazy_segment_tree_test {
-    use algebra::*;
     use segment_tree::persistent_lazy_segment_tree::PersistentLazySegmentTree;
     use std::cmp::min;
 
-    #[derive(Clone)]
-    struct Mm(usize);
-
-    impl Magma for Mm {
-        fn op(&self, right: &Self) -> Self { Mm(min(self.0, right.0)) }
-    }
-    impl Associative for Mm {}
-    impl Unital for Mm {
-        fn identity() -> Self { Mm(std::usize::MAX) }
-    }
-
-    #[derive(Clone)]
-    struct Uq(Option<usize>);
+    monoid!(Mm, usize, usize::MAX, |a, b| min(a, b));
 
-    impl Magma for Uq {
-        fn op(&self, right: &Self) -> Self {
-            if right.0.is_none() { self.clone() }
-            else { right.clone() }
-        }
-    }
-    impl Associative for Uq {}
-    impl Unital for Uq {
-        fn identity() -> Self { Uq(None) }
-    }
-    impl Effector for Uq {
-        type Target = Mm;
-        fn effect(&self, t: &Self::Target, _: usize) -> Self::Target {
-            match self.0 {
-                Some(u) => Mm(u),
-                None => t.clone(),
-            }
-        }
-    }
+    effector!(Uq, Option<usize>, None, |a, b| b.or(a), Mm, |e, t, _sz| Mm(e.unwrap_or(t.0)));
 
     #[test]
     fn rmq_ruq_test() {
@@ -146,40 +175,14 @@ mod persistent_lazy_segment_tree_test {
         let seg = seg.effect_range(0, 2, Uq(Some(1)));
         let seg = seg.effect_range(1, 3, Uq(Some(3)));
         let seg = seg.effect_range(2, 3, Uq(Some(2)));
-        assert_eq!(seg.fold(0, 3).0, 1);
-        assert_eq!(seg.fold(1, 3).0, 2);
+        assert_eq!(seg.fold(0..3).0, 1);
+        assert_eq!(seg.fold(1..3).0, 2);
     }
 
 
-    #[derive(Clone, Debug)]
-    struct Sm(usize);
-
-    impl Magma for Sm {
-        fn op(&self, right: &Self) -> Self { Sm(self.0 + right.0) }
-    }
-    impl Associative for Sm {}
-    impl Unital for Sm {
-        fn identity() -> Self { Sm(0) }
-    }
+    monoid!(#[derive(Debug)] Sm, usize, 0, |a, b| a + b);
 
-    #[derive(Clone, Debug)]
-    struct Aq(usize);
-
-    impl Magma for Aq {
-        fn op(&self, right: &Self) -> Self {
-            Aq(self.0 + right.0)
-        }
-    }
-    impl Associative for Aq {}
-    impl Unital for Aq {
-        fn identity() -> Self { Aq(0) }
-    }
-    impl Effector for Aq {
-        type Target = Sm;
-        fn effect(&self, t: &Self::Target, s: usize) -> Self::Target {
-            Sm(t.0 + self.0 * s)
-        }
-    }
+    effector!(#[derive(Debug)] Aq, usize, 0, |a, b| a + b, Sm, |e, t, s| Sm(t.0 + e * s));
 
     #[test]
     fn rsq_raq_test() {
@@ -187,8 +190,46 @@ mod persistent_lazy_segment_tree_test {
             .effect_range(0, 2, Aq(1))
             .effect_range(1, 3, Aq(2))
             .effect_range(2, 3, Aq(3));
-        assert_eq!(seg.fold(0, 2).0, 4);
-        assert_eq!(seg.fold(1, 3).0, 8);
+        assert_eq!(seg.fold(0..2).0, 4);
+        assert_eq!(seg.fold(1..3).0, 8);
 
     }
+
+    #[test]
+    fn from_vec_test() {
+        let v: Vec<usize> = (0..11).map(|i| i * 3 % 5).collect();
+        let seg = PersistentLazySegmentTree::from_vec(v.iter().map(|&x| Sm(x)).collect());
+        let seg2 = seg.effect_range(2, 9, Aq(10));
+        for l in 0..12 {
+            for r in l..12 {
+                let added = (l..r).filter(|i| (2..9).contains(i)).count() * 10;
+                assert_eq!(seg.fold(l..r).0, v[l..r].iter().sum::<usize>());
+                assert_eq!(seg2.fold(l..r).0, v[l..r].iter().sum::<usize>() + added);
+            }
+        }
+    }
+
+    #[test]
+    fn update_many_test() {
+        let v: Vec<usize> = (0..11).map(|i| i * 3 % 5).collect();
+        let seg = PersistentLazySegmentTree::from_vec(v.iter().map(|&x| Sm(x)).collect())
+            .effect_range(2, 9, Aq(10));
+        let seg2 = seg.update_many(vec![(4, Sm(100)), (0, Sm(7)), (8, Sm(1)), (4, Sm(50))]);
+        let seg3 = seg2.update(10, Sm(0)).effect_range(0, 6, Aq(1));
+        let mut w: Vec<usize> = v.iter().enumerate().map(|(i, &x)| if (2..9).contains(&i) { x + 10 } else { x }).collect();
+        let before = w.clone();
+        w[4] = 50;
+        w[0] = 7;
+        w[8] = 1;
+        let mut u = w.clone();
+        u[10] = 0;
+        for y in u[0..6].iter_mut() { *y += 1; }
+        for l in 0..12 {
+            for r in l..12 {
+                assert_eq!(seg.fold(l..r).0, before[l..r].iter().sum::<usize>());
+                assert_eq!(seg2.fold(l..r).0, w[l..r].iter().sum::<usize>());
+                assert_eq!(seg3.fold(l..r).0, u[l..r].iter().sum::<usize>());
+            }
+        }
+    }
 }
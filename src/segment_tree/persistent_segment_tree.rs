@@ -1,4 +1,6 @@
 use algebra::*;
+use segment_tree::{ RangeFold, bounds };
+use std::ops::RangeBounds;
 
 use std::rc::Rc;
 
@@ -12,7 +14,7 @@ struct Node<T: Monoid> {
 
 impl<T: Monoid> Node<T> {
     fn new(data: T) -> Self {
-        Node { data: data, left: None, right: None }
+        Node { data, left: None, right: None }
     }
     fn build(l: usize, r: usize) -> Self {
         if l + 1 >= r { Node::new(T::identity()) }
@@ -24,6 +26,19 @@ impl<T: Monoid> Node<T> {
             }
         }
     }
+    fn build_from<I: Iterator<Item=T>>(l: usize, r: usize, iter: &mut I) -> Self {
+        if l + 1 == r { Node::new(iter.next().unwrap()) }
+        else if l + 1 > r { Node::new(T::identity()) }
+        else {
+            let left = Node::build_from(l, (l + r) >> 1, iter);
+            let right = Node::build_from((l + r) >> 1, r, iter);
+            Node {
+                data: left.data.op(&right.data),
+                left: Some(Rc::new(left)),
+                right: Some(Rc::new(right)),
+            }
+        }
+    }
     fn update(&self, i: usize, x: T, l: usize, r: usize) -> Self {
         assert!(l <= i && i < r);
         if i == l && i + 1 == r { Node::new(x) }
@@ -33,8 +48,8 @@ impl<T: Monoid> Node<T> {
             Node {
                 data: match left.as_ref() { Some(n) => n.data.clone(), None => T::identity() }
                       .op(& match right.as_ref() { Some(n) => n.data.clone(), None => T::identity() }),
-                left: left,
-                right: right,
+                left,
+                right,
             }
         }
         else {
@@ -43,11 +58,24 @@ impl<T: Monoid> Node<T> {
             Node {
                 data: match left.as_ref() { Some(n) => n.data.clone(), None => T::identity() }
                       .op(& match right.as_ref() { Some(n) => n.data.clone(), None => T::identity() }),
-                left: left,
-                right: right,
+                left,
+                right,
             }
         }
     }
+    fn update_many(node: &Rc<Self>, ups: &[(usize, T)], l: usize, r: usize) -> Rc<Self> {
+        if ups.is_empty() { return node.clone(); }
+        if l + 1 >= r { return Rc::new(Node::new(ups[ups.len() - 1].1.clone())); }
+        let m = (l + r) >> 1;
+        let p = ups.partition_point(|u| u.0 < m);
+        let left = Node::update_many(node.left.as_ref().unwrap(), &ups[..p], l, m);
+        let right = Node::update_many(node.right.as_ref().unwrap(), &ups[p..], m, r);
+        Rc::new(Node {
+            data: left.data.op(&right.data),
+            left: Some(left),
+            right: Some(right),
+        })
+    }
     fn fold(&self, a: usize, b: usize, l: usize, r: usize) -> T {
         if a <= l && r <= b { self.data.clone() }
         else if r <= a || b <= l { T::identity() }
@@ -56,51 +84,138 @@ impl<T: Monoid> Node<T> {
                 .op(& match self.right.as_ref() { Some(n) => n.fold(a, b, (l + r) >> 1, r), None => T::identity() })
         }
     }
+    fn max_right<F: Fn(&T) -> bool>(&self, a: usize, l: usize, r: usize, pred: &F, acc: &mut T) -> usize {
+        if r <= a { return r; }
+        if a <= l {
+            let nx = acc.op(&self.data);
+            if pred(&nx) {
+                *acc = nx;
+                return r;
+            }
+            if l + 1 >= r { return l; }
+        }
+        let m = (l + r) >> 1;
+        let x = self.left.as_ref().unwrap().max_right(a, l, m, pred, acc);
+        if x < m { x }
+        else { self.right.as_ref().unwrap().max_right(a, m, r, pred, acc) }
+    }
+    fn min_left<F: Fn(&T) -> bool>(&self, b: usize, l: usize, r: usize, pred: &F, acc: &mut T) -> usize {
+        if b <= l { return l; }
+        if r <= b {
+            let nx = self.data.op(acc);
+            if pred(&nx) {
+                *acc = nx;
+                return l;
+            }
+            if l + 1 >= r { return r; }
+        }
+        let m = (l + r) >> 1;
+        let x = self.right.as_ref().unwrap().min_left(b, m, r, pred, acc);
+        if x > m { x }
+        else { self.left.as_ref().unwrap().min_left(b, l, m, pred, acc) }
+    }
 }
 
 impl<T: Monoid> Drop for Node<T> {
     fn drop(&mut self) {
         if let Some(left) = self.left.take() {
-            if let Ok(_) = Rc::try_unwrap(left) {}
+            let _ = Rc::try_unwrap(left);
         }
         if let Some(right) = self.right.take() {
-            if let Ok(_) = Rc::try_unwrap(right) {}
+            let _ = Rc::try_unwrap(right);
         }
     }
 }
 
+pub trait Count {
+    fn count(&self) -> usize;
+}
+
+fn kth_between<T: Monoid + Count>(older: &Node<T>, newer: &Node<T>, k: usize, l: usize, r: usize) -> usize {
+    if l + 1 >= r { return l; }
+    let m = (l + r) >> 1;
+    let (ol, nl) = (older.left.as_ref().unwrap(), newer.left.as_ref().unwrap());
+    let left = nl.data.count() - ol.data.count();
+    if k < left { kth_between(ol, nl, k, l, m) }
+    else { kth_between(older.right.as_ref().unwrap(), newer.right.as_ref().unwrap(), k - left, m, r) }
+}
+
 pub struct PersistentSegmentTree<T: Monoid> {
-    root: Node<T>,
+    root: Rc<Node<T>>,
     sz: usize,
 }
 
+impl<T: Monoid> Clone for PersistentSegmentTree<T> {
+    fn clone(&self) -> Self {
+        Self { root: self.root.clone(), sz: self.sz }
+    }
+}
+
 impl<T: Monoid> PersistentSegmentTree<T> {
     pub fn new(n: usize) -> Self {
-        Self { root: Node::build(0, n), sz: n }
+        Self { root: Rc::new(Node::build(0, n)), sz: n }
+    }
+    pub fn from_vec(vec: Vec<T>) -> Self {
+        let n = vec.len();
+        Self { root: Rc::new(Node::build_from(0, n, &mut vec.into_iter())), sz: n }
+    }
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        self.sz
     }
     pub fn update(&self, i: usize, x: T) -> Self {
-        Self { root: self.root.update(i, x, 0, self.sz), sz: self.sz }
+        Self { root: Rc::new(self.root.update(i, x, 0, self.sz)), sz: self.sz }
+    }
+    pub fn update_many<I: IntoIterator<Item=(usize, T)>>(&self, iter: I) -> Self {
+        let mut ups: Vec<(usize, T)> = iter.into_iter().collect();
+        assert!(ups.iter().all(|u| u.0 < self.sz), "update_many(): out of range");
+        ups.sort_by_key(|u| u.0);
+        Self { root: Node::update_many(&self.root, &ups, 0, self.sz), sz: self.sz }
     }
-    pub fn fold(&self, l: usize, r: usize) -> T {
+    pub fn fold<R: RangeBounds<usize>>(&self, range: R) -> T {
+        let (l, r) = bounds(&range, self.sz);
         self.root.fold(l, r, 0, self.sz)
     }
+    pub fn max_right<F: Fn(&T) -> bool>(&self, l: usize, pred: F) -> usize {
+        assert!(l <= self.sz, "max_right(): out of range");
+        self.root.max_right(l, 0, self.sz, &pred, &mut T::identity())
+    }
+    pub fn min_left<F: Fn(&T) -> bool>(&self, r: usize, pred: F) -> usize {
+        assert!(r <= self.sz, "min_left(): out of range");
+        self.root.min_left(r, 0, self.sz, &pred, &mut T::identity())
+    }
 }
 
-#[cfg(test)]
-mod rsq_test {
-    use algebra::*;
-    use segment_tree::persistent_segment_tree::PersistentSegmentTree;
+impl<T: Monoid + Count> PersistentSegmentTree<T> {
+    // the versions may be passed in either order; the one holding fewer elements is the older
+    fn ordered<'a>(a: &'a Self, b: &'a Self) -> (&'a Self, &'a Self) {
+        if a.root.data.count() <= b.root.data.count() { (a, b) } else { (b, a) }
+    }
+    pub fn kth_between(older: &Self, newer: &Self, k: usize) -> Option<usize> {
+        assert!(older.sz == newer.sz, "kth_between(): the trees have different sizes");
+        let (older, newer) = Self::ordered(older, newer);
+        if older.sz == 0 || newer.root.data.count() - older.root.data.count() <= k { None }
+        else { Some(kth_between(&older.root, &newer.root, k, 0, newer.sz)) }
+    }
+    pub fn count_less_between(older: &Self, newer: &Self, x: usize) -> usize {
+        assert!(older.sz == newer.sz, "count_less_between(): the trees have different sizes");
+        let (older, newer) = Self::ordered(older, newer);
+        newer.fold(..x).count() - older.fold(..x).count()
+    }
+}
 
-    #[derive(Clone, Debug)]
-    struct Am(usize);
+impl<T: Monoid> RangeFold for PersistentSegmentTree<T> {
+    type Value = T;
+    fn fold<R: RangeBounds<usize>>(&self, range: R) -> T { PersistentSegmentTree::fold(self, range) }
+}
 
-    impl Magma for Am {
-        fn op(&self, right: &Self) -> Self { Am(self.0 + right.0) }
-    }
-    impl Associative for Am {}
+#[cfg(test)]
+mod rsq_test {
+    use segment_tree::persistent_segment_tree::{ PersistentSegmentTree, Count };
 
-    impl Unital for Am {
-        fn identity() -> Self { Am(0) }
+    monoid!(#[derive(Debug)] Am, usize, 0, |a, b| a + b);
+    impl Count for Am {
+        fn count(&self) -> usize { self.0 }
     }
     #[test]
     fn rsq_test() {
@@ -108,7 +223,73 @@ mod rsq_test {
         let seg = seg.update(0, Am(1));
         let seg = seg.update(1, Am(2));
         let seg = seg.update(2, Am(3));
-        assert_eq!(seg.fold(0, 2).0, 3);
-        assert_eq!(seg.fold(1, 2).0, 2);
+        assert_eq!(seg.fold(0..2).0, 3);
+        assert_eq!(seg.fold(1..2).0, 2);
+    }
+    #[test]
+    fn binary_search_test() {
+        let v = [3, 1, 4, 1, 5, 9, 2];
+        let seg = v.iter().enumerate().fold(PersistentSegmentTree::new(v.len()), |seg, (i, x)| seg.update(i, Am(*x)));
+        for l in 0..v.len() + 1 {
+            for k in 0..30 {
+                let expected = (l..v.len() + 1).take_while(|&r| v[l..r].iter().sum::<usize>() <= k).last().unwrap();
+                assert_eq!(seg.max_right(l, |x| x.0 <= k), expected);
+            }
+        }
+        for r in 0..v.len() + 1 {
+            for k in 0..30 {
+                let expected = (0..r + 1).rev().take_while(|&l| v[l..r].iter().sum::<usize>() <= k).last().unwrap();
+                assert_eq!(seg.min_left(r, |x| x.0 <= k), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn kth_smallest_test() {
+        let a = [5, 1, 4, 1, 9, 2, 6, 5, 3];
+        let mut vals: Vec<usize> = a.to_vec();
+        vals.sort();
+        vals.dedup();
+        let mut versions = vec![PersistentSegmentTree::<Am>::new(vals.len())];
+        for x in a.iter() {
+            let p = vals.binary_search(x).unwrap();
+            let last = versions.last().unwrap().clone();
+            let c = last.fold(p..p + 1).0;
+            versions.push(last.update(p, Am(c + 1)));
+        }
+        for l in 0..a.len() {
+            for r in l..a.len() + 1 {
+                let mut sorted = a[l..r].to_vec();
+                sorted.sort();
+                for k in 0..r - l + 1 {
+                    let kth = PersistentSegmentTree::kth_between(&versions[l], &versions[r], k);
+                    assert_eq!(kth.map(|p| vals[p]), sorted.get(k).cloned());
+                    assert_eq!(PersistentSegmentTree::kth_between(&versions[r], &versions[l], k), kth);
+                }
+                for (p, v) in vals.iter().enumerate() {
+                    let less = PersistentSegmentTree::count_less_between(&versions[l], &versions[r], p);
+                    assert_eq!(less, sorted.iter().filter(|&x| x < v).count());
+                    assert_eq!(PersistentSegmentTree::count_less_between(&versions[r], &versions[l], p), less);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn from_vec_test() {
+        let v: Vec<usize> = (0..13).map(|i| i * i % 7).collect();
+        let seg = PersistentSegmentTree::from_vec(v.iter().map(|&x| Am(x)).collect());
+        let seg2 = seg.update_many(vec![(3, Am(100)), (12, Am(200)), (4, Am(0)), (3, Am(50))]);
+        let mut w = v.clone();
+        w[3] = 50;
+        w[12] = 200;
+        w[4] = 0;
+        for l in 0..14 {
+            for r in l..14 {
+                assert_eq!(seg.fold(l..r).0, v[l..r].iter().sum::<usize>());
+                assert_eq!(seg2.fold(l..r).0, w[l..r].iter().sum::<usize>());
+            }
+        }
+        assert_eq!(PersistentSegmentTree::<Am>::from_vec(vec![]).fold(..).0, 0);
     }
 }
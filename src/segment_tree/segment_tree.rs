@@ -1,8 +1,11 @@
 use algebra::*;
+use segment_tree::{ RangeFold, bounds };
+use std::ops::RangeBounds;
 
 pub struct SegmentTree<T: Monoid> {
     node: Vec<T>,
     sz: usize,
+    n: usize,
 }
 
 impl<T: Monoid> SegmentTree<T> {
@@ -10,61 +13,224 @@ impl<T: Monoid> SegmentTree<T> {
         let mut sz = 1;
         while sz < vec.len() { sz *= 2; }
         let mut node = vec![T::identity(); sz << 1];
-        for i in 0..vec.len() { node[i + sz] = vec[i].clone(); }
+        node[sz..(vec.len() + sz)].clone_from_slice(&vec);
         for i in (1..sz).rev() { node[i] = node[i << 1].op(&node[(i << 1) + 1]); }
-        SegmentTree { node: node, sz: sz }
+        SegmentTree { node, sz, n: vec.len() }
     }
 
     pub fn update(&mut self, i: usize, x: T) {
+        assert!(i < self.n, "update(): out of range");
         let mut idx = i + self.sz;
         self.node[idx] = x;
         while idx > 1 {
-            idx = idx >> 1;
+            idx >>= 1;
             self.node[idx] = self.node[idx << 1].op(&self.node[(idx << 1)  + 1]);
         }
     }
 
-    pub fn fold(&self, left: usize, right: usize) -> T {
+    pub fn update_with<F: FnOnce(&T) -> T>(&mut self, i: usize, f: F) {
+        assert!(i < self.n, "update_with(): out of range");
+        let x = f(&self.node[i + self.sz]);
+        self.update(i, x);
+    }
+
+    pub fn update_many<I: IntoIterator<Item=(usize, T)>>(&mut self, iter: I) {
+        let mut idx: Vec<usize> = iter.into_iter().map(|(i, x)| {
+            assert!(i < self.n, "update_many(): out of range");
+            self.node[i + self.sz] = x;
+            (i + self.sz) >> 1
+        }).collect();
+        idx.sort();
+        idx.dedup();
+        while !idx.is_empty() && idx[0] > 0 {
+            for &i in idx.iter() {
+                self.node[i] = self.node[i << 1].op(&self.node[(i << 1) + 1]);
+            }
+            for i in idx.iter_mut() { *i >>= 1; }
+            idx.dedup();
+        }
+    }
+
+    pub fn get(&self, i: usize) -> &T {
+        assert!(i < self.n, "get(): out of range");
+        &self.node[i + self.sz]
+    }
+
+    pub fn len(&self) -> usize { self.n }
+
+    pub fn is_empty(&self) -> bool { self.n == 0 }
+
+    pub fn as_slice(&self) -> &[T] {
+        &self.node[self.sz..(self.sz + self.n)]
+    }
+
+    pub fn fold<R: RangeBounds<usize>>(&self, range: R) -> T {
+        let (left, right) = bounds(&range, self.n);
         let mut lx = T::identity();
         let mut rx = T::identity();
         let mut l = left + self.sz;
         let mut r = right + self.sz;
         while l < r {
-            if (l & 1) == 1 { lx = lx.op(&self.node[l]); }
-            if (r & 1) == 0 { rx = self.node[r].op(&rx); }
-            l = (l + 1) >> 1;
-            r = (r - 1) >> 1;
+            if (l & 1) == 1 {
+                lx = lx.op(&self.node[l]);
+                l += 1;
+            }
+            if (r & 1) == 1 {
+                r -= 1;
+                rx = self.node[r].op(&rx);
+            }
+            l >>= 1;
+            r >>= 1;
         }
-        if l == r { lx = lx.op(&self.node[l]); }
         lx.op(&rx)
     }
+
+    pub fn max_right<F: Fn(&T) -> bool>(&self, left: usize, pred: F) -> usize {
+        assert!(left <= self.n, "max_right(): out of range");
+        if left == self.n { return self.n; }
+        let mut l = left + self.sz;
+        let mut acc = T::identity();
+        loop {
+            while (l & 1) == 0 { l >>= 1; }
+            if !pred(&acc.op(&self.node[l])) {
+                while l < self.sz {
+                    l <<= 1;
+                    let nx = acc.op(&self.node[l]);
+                    if pred(&nx) {
+                        acc = nx;
+                        l += 1;
+                    }
+                }
+                return l - self.sz;
+            }
+            acc = acc.op(&self.node[l]);
+            l += 1;
+            if (l & l.wrapping_neg()) == l { return self.n; }
+        }
+    }
+
+    pub fn min_left<F: Fn(&T) -> bool>(&self, right: usize, pred: F) -> usize {
+        assert!(right <= self.n, "min_left(): out of range");
+        if right == 0 { return 0; }
+        let mut r = right + self.sz;
+        let mut acc = T::identity();
+        loop {
+            r -= 1;
+            while r > 1 && (r & 1) == 1 { r >>= 1; }
+            if !pred(&self.node[r].op(&acc)) {
+                while r < self.sz {
+                    r = (r << 1) + 1;
+                    let nx = self.node[r].op(&acc);
+                    if pred(&nx) {
+                        acc = nx;
+                        r -= 1;
+                    }
+                }
+                return r + 1 - self.sz;
+            }
+            acc = self.node[r].op(&acc);
+            if (r & r.wrapping_neg()) == r { return 0; }
+        }
+    }
+}
+
+impl<T: Monoid + Commutative> SegmentTree<T> {
+    pub fn fold_unordered<R: RangeBounds<usize>>(&self, range: R) -> T {
+        let (left, right) = bounds(&range, self.n);
+        let mut x = T::identity();
+        let mut l = left + self.sz;
+        let mut r = right + self.sz;
+        while l < r {
+            if (l & 1) == 1 {
+                x = x.op(&self.node[l]);
+                l += 1;
+            }
+            if (r & 1) == 1 {
+                r -= 1;
+                x = x.op(&self.node[r]);
+            }
+            l >>= 1;
+            r >>= 1;
+        }
+        x
+    }
+}
+
+impl<T: Monoid> RangeFold for SegmentTree<T> {
+    type Value = T;
+    fn fold<R: RangeBounds<usize>>(&self, range: R) -> T { SegmentTree::fold(self, range) }
 }
 
 #[cfg(test)]
 mod rsq_test {
-    use algebra::*;
     use segment_tree::segment_tree::SegmentTree;
 
-    #[derive(Clone)]
-    struct Am(usize);
-
-    impl Magma for Am {
-        fn op(&self, right: &Self) -> Self { Am(self.0 + right.0) }
-    }
-    impl Associative for Am {}
+    monoid!(Am, usize, 0, |a, b| a + b; Commutative);
 
-    impl Unital for Am {
-        fn identity() -> Self { Am(0) }
-    }
     #[test]
     fn rsq_test() {
         let seg = SegmentTree::init(vec![Am(1), Am(2), Am(3)]);
-        assert!(seg.fold(0, 1).0 == 3);
-        assert!(seg.fold(1, 1).0 == 2);
+        assert!(seg.fold_unordered(0..3).0 == 6);
+        assert!(seg.fold_unordered(1..).0 == 5);
+        assert!(seg.fold(0..2).0 == 3);
+        assert!(seg.fold(1..2).0 == 2);
+        assert!(seg.fold(1..).0 == 5);
+        assert!(seg.fold(..=1).0 == 3);
+        assert!(seg.fold(..).0 == 6);
+        assert!(seg.fold(2..2).0 == 0);
+    }
+    #[test]
+    fn binary_search_test() {
+        let v = [3, 1, 4, 1, 5, 9, 2, 6];
+        let seg = SegmentTree::init(v.iter().map(|x| Am(*x)).collect());
+        for l in 0..v.len() + 1 {
+            for k in 0..30 {
+                let expected = (l..v.len() + 1).take_while(|&r| v[l..r].iter().sum::<usize>() <= k).last().unwrap();
+                assert_eq!(seg.max_right(l, |x| x.0 <= k), expected);
+            }
+        }
+        for r in 0..v.len() + 1 {
+            for k in 0..30 {
+                let expected = (0..r + 1).rev().take_while(|&l| v[l..r].iter().sum::<usize>() <= k).last().unwrap();
+                assert_eq!(seg.min_left(r, |x| x.0 <= k), expected);
+            }
+        }
+    }
+    #[test]
+    fn range_fold_trait_test() {
+        use segment_tree::RangeFold;
+        use segment_tree::persistent_segment_tree::PersistentSegmentTree;
+        fn middle<S: RangeFold<Value=Am>>(seg: &S) -> usize { seg.fold(1..=2).0 }
+        let seg = SegmentTree::init(vec![Am(1), Am(2), Am(3), Am(4)]);
+        let pseg = PersistentSegmentTree::new(4).update(1, Am(2)).update(2, Am(3));
+        assert_eq!(middle(&seg), 5);
+        assert_eq!(middle(&pseg), 5);
+    }
+    #[test]
+    fn point_access_test() {
+        let mut seg = SegmentTree::init((0..10).map(Am).collect());
+        assert_eq!(seg.len(), 10);
+        assert_eq!(seg.get(3).0, 3);
+        seg.update_with(3, |x| Am(x.0 * 10));
+        assert_eq!(seg.get(3).0, 30);
+        seg.update_many(vec![(0, Am(100)), (9, Am(200)), (4, Am(0)), (0, Am(50))]);
+        let expected = [50, 1, 2, 30, 0, 5, 6, 7, 8, 200];
+        assert!(seg.as_slice().iter().map(|x| x.0).eq(expected.iter().cloned()));
+        for l in 0..11 {
+            for r in l..11 {
+                assert_eq!(seg.fold(l..r).0, expected[l..r].iter().sum::<usize>());
+            }
+        }
+    }
+    #[test]
+    #[should_panic(expected = "update(): out of range")]
+    fn padding_update_test() {
+        let mut seg = SegmentTree::init(vec![Am(1), Am(2), Am(3)]);
+        seg.update(3, Am(4));
     }
     #[test]
     fn corner_test() {
         let seg = SegmentTree::init(vec![Am(1)]);
-        assert!(seg.fold(0, 0).0 == 1);
+        assert!(seg.fold(0..1).0 == 1);
     }
 }
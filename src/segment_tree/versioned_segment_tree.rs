@@ -0,0 +1,74 @@
+use algebra::*;
+use segment_tree::persistent_segment_tree::PersistentSegmentTree;
+use segment_tree::versions::Versions;
+use std::ops::RangeBounds;
+
+pub struct VersionedSegmentTree<T: Monoid> {
+    versions: Versions<PersistentSegmentTree<T>>,
+}
+
+impl<T: Monoid> VersionedSegmentTree<T> {
+    pub fn new(n: usize) -> Self {
+        Self::from_tree(PersistentSegmentTree::new(n))
+    }
+    pub fn from_tree(tree: PersistentSegmentTree<T>) -> Self {
+        Self { versions: Versions::new(tree) }
+    }
+    pub fn version(&self, version: usize) -> &PersistentSegmentTree<T> {
+        self.versions.get(version)
+    }
+    pub fn contains(&self, version: usize) -> bool {
+        self.versions.contains(version)
+    }
+    pub fn latest(&self) -> usize {
+        self.versions.latest()
+    }
+    pub fn update(&mut self, version: usize, i: usize, x: T) -> usize {
+        let tree = self.version(version).update(i, x);
+        self.versions.push(tree)
+    }
+    pub fn fold<R: RangeBounds<usize>>(&self, version: usize, range: R) -> T {
+        self.version(version).fold(range)
+    }
+    pub fn rollback(&mut self, version: usize) {
+        self.versions.rollback(version);
+    }
+    pub fn release(&mut self, version: usize) {
+        self.versions.release(version);
+    }
+    pub fn retain<F: FnMut(usize) -> bool>(&mut self, f: F) {
+        self.versions.retain(f);
+    }
+}
+
+#[cfg(test)]
+mod versioned_segment_tree_test {
+    use segment_tree::versioned_segment_tree::VersionedSegmentTree;
+
+    monoid!(#[derive(Debug)] Am, usize, 0, |a, b| a + b);
+
+    #[test]
+    fn version_test() {
+        let mut seg = VersionedSegmentTree::new(4);
+        let v1 = seg.update(0, 1, Am(5));
+        let v2 = seg.update(v1, 2, Am(7));
+        let v3 = seg.update(v1, 3, Am(1));
+        assert_eq!(seg.fold(0, ..).0, 0);
+        assert_eq!(seg.fold(v1, ..).0, 5);
+        assert_eq!(seg.fold(v2, 1..3).0, 12);
+        assert_eq!(seg.fold(v3, ..).0, 6);
+        assert_eq!(seg.latest(), v3);
+        seg.rollback(v2);
+        assert_eq!(seg.latest(), v2);
+        assert!(!seg.contains(v3));
+        seg.release(v1);
+        assert!(!seg.contains(v1));
+        assert_eq!(seg.fold(v2, ..).0, 12);
+        let v4 = seg.update(v2, 0, Am(100));
+        assert!(v4 > v3 && !seg.contains(v3));
+        assert_eq!(seg.latest(), v4);
+        seg.retain(|v| v == v4);
+        assert!(!seg.contains(0) && !seg.contains(v2));
+        assert_eq!(seg.fold(v4, ..).0, 112);
+    }
+}
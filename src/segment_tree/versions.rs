@@ -0,0 +1,83 @@
+// version ids handed out by the persistent trees: ids are never reused,
+// a rolled back or released id stays dead forever
+pub struct Versions<R> {
+    roots: Vec<Option<R>>,
+    latest: usize,
+}
+
+impl<R> Versions<R> {
+    pub fn new(root: R) -> Self {
+        Self { roots: vec![Some(root)], latest: 0 }
+    }
+    pub fn get(&self, version: usize) -> &R {
+        match self.roots.get(version) {
+            Some(Some(root)) => root,
+            _ => panic!("version {} does not exist", version),
+        }
+    }
+    pub fn contains(&self, version: usize) -> bool {
+        matches!(self.roots.get(version), Some(&Some(_)))
+    }
+    pub fn latest(&self) -> usize {
+        self.latest
+    }
+    pub fn push(&mut self, root: R) -> usize {
+        self.roots.push(Some(root));
+        self.latest = self.roots.len() - 1;
+        self.latest
+    }
+    pub fn rollback(&mut self, version: usize) {
+        assert!(self.contains(version), "rollback(): version {} does not exist", version);
+        for root in self.roots[version + 1..].iter_mut() { *root = None; }
+        self.latest = version;
+    }
+    pub fn release(&mut self, version: usize) {
+        assert!(self.contains(version), "release(): version {} does not exist", version);
+        self.roots[version] = None;
+    }
+    pub fn retain<F: FnMut(usize) -> bool>(&mut self, mut f: F) {
+        for (i, v) in self.roots.iter_mut().enumerate() {
+            if v.is_some() && !f(i) { *v = None; }
+        }
+    }
+}
+
+pub const NIL: usize = !0;
+
+// a node of an arena-backed tree, linking to its children by index (`NIL` for none)
+pub trait ArenaNode {
+    fn children(&mut self) -> (&mut usize, &mut usize);
+}
+
+impl Versions<usize> {
+    // drops every node unreachable from a live version and renumbers the rest
+    pub fn compact<N: ArenaNode>(&mut self, nodes: &mut Vec<N>) {
+        let mut idx = vec![NIL; nodes.len()];
+        let mut stack: Vec<usize> = self.roots.iter().filter_map(|&r| r).collect();
+        while let Some(v) = stack.pop() {
+            if v == NIL || idx[v] != NIL { continue; }
+            idx[v] = 0;
+            let (left, right) = nodes[v].children();
+            stack.push(*left);
+            stack.push(*right);
+        }
+        for (cnt, i) in idx.iter_mut().filter(|i| **i != NIL).enumerate() {
+            *i = cnt;
+        }
+        let old = std::mem::take(nodes);
+        *nodes = old.into_iter().enumerate()
+            .filter(|&(i, _)| idx[i] != NIL)
+            .map(|(_, mut node)| {
+                {
+                    let (left, right) = node.children();
+                    if *left != NIL { *left = idx[*left]; }
+                    if *right != NIL { *right = idx[*right]; }
+                }
+                node
+            })
+            .collect();
+        for root in self.roots.iter_mut() {
+            if let Some(ref mut r) = *root { *r = idx[*r]; }
+        }
+    }
+}
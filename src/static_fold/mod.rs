@@ -0,0 +1,2 @@
+pub mod sparse_table;
+pub mod prefix_fold;
@@ -0,0 +1,62 @@
+use algebra::*;
+use segment_tree::{ RangeFold, bounds };
+use std::ops::RangeBounds;
+
+pub struct PrefixFold<T: Group> {
+    prefix: Vec<T>,
+}
+
+impl<T: Group> PrefixFold<T> {
+    pub fn init(vec: Vec<T>) -> Self {
+        let mut prefix = Vec::with_capacity(vec.len() + 1);
+        prefix.push(T::identity());
+        for x in vec.iter() {
+            let next = prefix[prefix.len() - 1].op(x);
+            prefix.push(next);
+        }
+        PrefixFold { prefix }
+    }
+
+    pub fn len(&self) -> usize { self.prefix.len() - 1 }
+
+    pub fn is_empty(&self) -> bool { self.prefix.len() == 1 }
+
+    pub fn fold<R: RangeBounds<usize>>(&self, range: R) -> T {
+        let (l, r) = bounds(&range, self.len());
+        self.prefix[l].inverse().op(&self.prefix[r])
+    }
+}
+
+impl<T: Group> RangeFold for PrefixFold<T> {
+    type Value = T;
+    fn fold<R: RangeBounds<usize>>(&self, range: R) -> T { PrefixFold::fold(self, range) }
+}
+
+#[cfg(test)]
+mod prefix_fold_test {
+    use algebra::*;
+    use static_fold::prefix_fold::PrefixFold;
+
+    monoid!(#[derive(Debug, PartialEq)] Perm, [usize; 3], [0, 1, 2], |a, b| [b[a[0]], b[a[1]], b[a[2]]]);
+
+    impl Invertible for Perm {
+        fn inverse(&self) -> Self {
+            let mut inv = [0; 3];
+            for i in 0..3 { inv[self.0[i]] = i; }
+            Perm(inv)
+        }
+    }
+
+    #[test]
+    fn non_commutative_group_test() {
+        let gens = [Perm([1, 0, 2]), Perm([0, 2, 1]), Perm([1, 2, 0]), Perm([2, 1, 0])];
+        let v: Vec<Perm> = (0..11).map(|i| gens[i * 7 % 4].clone()).collect();
+        let pf = PrefixFold::init(v.clone());
+        for l in 0..v.len() + 1 {
+            for r in l..v.len() + 1 {
+                let expected = v[l..r].iter().fold(Perm::identity(), |acc, x| acc.op(x));
+                assert_eq!(pf.fold(l..r), expected);
+            }
+        }
+    }
+}
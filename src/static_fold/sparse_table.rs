@@ -0,0 +1,61 @@
+use algebra::*;
+use segment_tree::{ RangeFold, bounds };
+use std::ops::RangeBounds;
+
+pub struct SparseTable<T: Monoid + Idempotent> {
+    table: Vec<Vec<T>>,
+    n: usize,
+}
+
+impl<T: Monoid + Idempotent> SparseTable<T> {
+    pub fn init(vec: Vec<T>) -> Self {
+        let n = vec.len();
+        let mut table = vec![vec];
+        let mut k = 1;
+        while (1 << k) <= n {
+            let next: Vec<T> = {
+                let prev = &table[k - 1];
+                (0..n + 1 - (1 << k)).map(|i| prev[i].op(&prev[i + (1 << (k - 1))])).collect()
+            };
+            table.push(next);
+            k += 1;
+        }
+        SparseTable { table, n }
+    }
+
+    pub fn len(&self) -> usize { self.n }
+
+    pub fn is_empty(&self) -> bool { self.n == 0 }
+
+    pub fn fold<R: RangeBounds<usize>>(&self, range: R) -> T {
+        let (l, r) = bounds(&range, self.n);
+        if l == r { return T::identity(); }
+        let k = (usize::BITS - 1 - (r - l).leading_zeros()) as usize;
+        self.table[k][l].op(&self.table[k][r - (1 << k)])
+    }
+}
+
+impl<T: Monoid + Idempotent> RangeFold for SparseTable<T> {
+    type Value = T;
+    fn fold<R: RangeBounds<usize>>(&self, range: R) -> T { SparseTable::fold(self, range) }
+}
+
+#[cfg(test)]
+mod sparse_table_test {
+    use static_fold::sparse_table::SparseTable;
+    use std::cmp::min;
+
+    monoid!(#[derive(Debug)] Mm, usize, usize::MAX, |a, b| min(a, b); Commutative, Idempotent);
+
+    #[test]
+    fn rmq_test() {
+        let v: Vec<usize> = (0..37).map(|i| i * 17 % 23).collect();
+        let st = SparseTable::init(v.iter().map(|&x| Mm(x)).collect());
+        for l in 0..v.len() + 1 {
+            for r in l..v.len() + 1 {
+                assert_eq!(st.fold(l..r).0, v[l..r].iter().cloned().min().unwrap_or(usize::MAX));
+            }
+        }
+        assert_eq!(SparseTable::<Mm>::init(vec![]).fold(..).0, usize::MAX);
+    }
+}